@@ -1,11 +1,22 @@
 use std::fs;
+use std::io;
 extern crate slog;
-extern crate slog_term;
 use slog::*;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Once, RwLock};
+
+use directories::ProjectDirs;
+
+/// The version of the configuration schema, cf. `migrate_config`
+pub const CONFIG_VERSION: u32 = 5;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
 pub struct AdrToolConfig {
+    /// The version of the schema the configuration file has been written with (0 for the files written before the
+    /// schema was versioned)
+    pub version: u32,
     pub log_level: usize,
     //pub adr_root_dir: String,
     pub adr_src_dir: String,
@@ -14,6 +25,53 @@ pub struct AdrToolConfig {
     pub adr_search_index: String,
     pub use_id_prefix: bool,
     pub id_prefix_width: usize,
+    /// Number of days a Decision Record may stay wip before being reported by `adr due` (0 to disable)
+    #[serde(default = "default_wip_max_age_days")]
+    pub wip_max_age_days: u32,
+    /// Number of approvals needed before a Decision Record can be decided (0 to make reviews optional)
+    #[serde(default)]
+    pub review_quorum: usize,
+    /// Commit (with git) the Decision Records touched by `adr lf` and `adr review` commands
+    #[serde(default)]
+    pub auto_commit: bool,
+    #[serde(default)]
+    pub allowed_tags: Vec<TagDefinition>,
+    /// The name of the current workspace (none when empty), cf. `workspaces`
+    pub workspace: String,
+    /// The named Decision Records repositories (e.g. a platform log and product logs), each with its own directories
+    pub workspaces: Vec<WorkspaceDefinition>,
+    /// The directory of the search index spanning all the workspaces
+    pub federated_search_index: String,
+    /// Update the search index before searching when the Decision Records have changed since it has been built
+    pub search_auto_refresh: bool,
+    /// The extensions of the Decision Records files (e.g. `adoc`)
+    pub adr_extensions: Vec<String>,
+    /// The gitignore-style globs the Decision Records files must match, relative to `adr_src_dir` (all the files when
+    /// empty)
+    pub adr_include: Vec<String>,
+    /// The gitignore-style globs of the files of `adr_src_dir` which are not Decision Records, in addition to the
+    /// ones of its `.adrignore` file
+    pub adr_exclude: Vec<String>,
+}
+
+/// A tag of the controlled vocabulary. When `allowed_tags` is empty, tags are free text.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct TagDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+/// A named Decision Records repository. When it is the current workspace, its directories replace the ones of the
+/// configuration, so that the search index, the templates and the ID sequence are kept per workspace.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct WorkspaceDefinition {
+    pub name: String,
+    pub adr_src_dir: String,
+    pub adr_template_dir: String,
+    pub adr_search_index: String,
 }
 
 pub const LOG_LEVEL: &str = "log_level";
@@ -21,13 +79,306 @@ pub const ADR_ROOT_DIR: &str = "adr_root_dir";
 pub const ADR_SRC_DIR: &str = "adr_src_dir";
 pub const ADR_TEMPLATE_DIR: &str = "adr_template_dir";
 pub const ADR_TEMPLATE_FILE: &str = "adr_template_file";
-pub const ADR_SEARCH_INDEX: &str = "adr_search_dir";
+pub const ADR_SEARCH_INDEX: &str = "adr_search_index";
+pub const FEDERATED_SEARCH_INDEX: &str = "federated_search_index";
+pub const SEARCH_AUTO_REFRESH: &str = "search_auto_refresh";
+pub const ADR_EXTENSIONS: &str = "adr_extensions";
+pub const ADR_INCLUDE: &str = "adr_include";
+pub const ADR_EXCLUDE: &str = "adr_exclude";
 pub const USE_ID_PREFIX: &str = "use_id_prefix";
 pub const ID_PREFIX_WIDTH: &str = "id_prefix_width";
+pub const ALLOWED_TAGS: &str = "allowed_tags";
+pub const WIP_MAX_AGE_DAYS: &str = "wip_max_age_days";
+pub const REVIEW_QUORUM: &str = "review_quorum";
+pub const AUTO_COMMIT: &str = "auto_commit";
+pub const WORKSPACE: &str = "workspace";
+pub const WORKSPACES: &str = "workspaces";
+
+/// The type of a property of the configuration, which drives how its value is parsed and checked
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum PropertyType {
+    /// a directory, relative to the current directory when set
+    PATH,
+    STRING,
+    /// a positive integer
+    INTEGER,
+    BOOLEAN,
+    /// a list of structured values, which can only be modified in the configuration file (cf. `adr config edit`)
+    LIST,
+}
+
+impl PropertyType {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            PropertyType::PATH => "path",
+            PropertyType::STRING => "string",
+            PropertyType::INTEGER => "integer",
+            PropertyType::BOOLEAN => "boolean",
+            PropertyType::LIST => "list",
+        }
+    }
+}
+
+/// A property of the configuration, cf. `CONFIG_PROPERTIES`
+#[derive(Debug)]
+pub struct ConfigProperty {
+    /// The name of the property, as in the configuration files
+    pub name: &'static str,
+    pub property_type: PropertyType,
+    pub description: &'static str,
+    /// The allowed values of an `INTEGER` property, if limited
+    pub range: Option<(u64, u64)>,
+}
+
+impl ConfigProperty {
+    pub fn is_modifiable(&self) -> bool {
+        self.property_type != PropertyType::LIST
+    }
+
+    /// Checks the value of the property, as given to `adr config set`, by an environment variable or on the command line
+    pub fn validate(&self, value: &str) -> std::result::Result<(), String> {
+        match self.property_type {
+            PropertyType::PATH | PropertyType::STRING if value.trim().is_empty() => {
+                Err(format!("[{}] cannot be empty", self.name))
+            }
+            PropertyType::PATH | PropertyType::STRING => Ok(()),
+            PropertyType::INTEGER => match (value.parse::<u64>(), self.range) {
+                (Err(_why), _) => Err(format!("[{}] is not a number", value)),
+                (Ok(value), Some((min, max))) if value < min || value > max => Err(format!(
+                    "[{}] must be between [{}] and [{}]",
+                    self.name, min, max
+                )),
+                (Ok(_), _) => Ok(()),
+            },
+            PropertyType::BOOLEAN => match value.parse::<bool>() {
+                Ok(_) => Ok(()),
+                Err(_why) => Err(format!("[{}] is not a boolean (true or false)", value)),
+            },
+            PropertyType::LIST => Err(format!(
+                "[{}] can only be modified in the configuration file (cf. adr config edit)",
+                self.name
+            )),
+        }
+    }
+}
+
+/// The properties of the configuration (cf. `AdrToolConfig`), in the order they are listed
+pub static CONFIG_PROPERTIES: [ConfigProperty; 18] = [
+    ConfigProperty {
+        name: ADR_SRC_DIR,
+        property_type: PropertyType::PATH,
+        description: "The directory of the Decision Records",
+        range: None,
+    },
+    ConfigProperty {
+        name: ADR_TEMPLATE_DIR,
+        property_type: PropertyType::PATH,
+        description: "The directory of the templates",
+        range: None,
+    },
+    ConfigProperty {
+        name: ADR_TEMPLATE_FILE,
+        property_type: PropertyType::STRING,
+        description: "The template of the new Decision Records, in the template directory",
+        range: None,
+    },
+    ConfigProperty {
+        name: ADR_SEARCH_INDEX,
+        property_type: PropertyType::PATH,
+        description: "The directory of the search index",
+        range: None,
+    },
+    ConfigProperty {
+        name: LOG_LEVEL,
+        property_type: PropertyType::INTEGER,
+        description: "The log level, from 1 (critical) to 6 (trace)",
+        range: Some((1, 6)),
+    },
+    ConfigProperty {
+        name: USE_ID_PREFIX,
+        property_type: PropertyType::BOOLEAN,
+        description: "Prefix the file of the new Decision Records with a sequential ID",
+        range: None,
+    },
+    ConfigProperty {
+        name: ID_PREFIX_WIDTH,
+        property_type: PropertyType::INTEGER,
+        description: "The number of digits of the ID prefix",
+        range: Some((1, 32)),
+    },
+    ConfigProperty {
+        name: WIP_MAX_AGE_DAYS,
+        property_type: PropertyType::INTEGER,
+        description:
+            "The number of days a Decision Record may stay wip before being due (0 to disable)",
+        range: None,
+    },
+    ConfigProperty {
+        name: REVIEW_QUORUM,
+        property_type: PropertyType::INTEGER,
+        description: "The number of approvals needed to decide (0 to make reviews optional)",
+        range: None,
+    },
+    ConfigProperty {
+        name: AUTO_COMMIT,
+        property_type: PropertyType::BOOLEAN,
+        description: "Commit the Decision Records changed by adr lf and adr review",
+        range: None,
+    },
+    ConfigProperty {
+        name: ALLOWED_TAGS,
+        property_type: PropertyType::LIST,
+        description: "The controlled vocabulary of tags (free text when empty)",
+        range: None,
+    },
+    ConfigProperty {
+        name: WORKSPACE,
+        property_type: PropertyType::STRING,
+        description: "The current workspace (cf. adr workspace use)",
+        range: None,
+    },
+    ConfigProperty {
+        name: WORKSPACES,
+        property_type: PropertyType::LIST,
+        description: "The named Decision Records repositories (cf. adr workspace add)",
+        range: None,
+    },
+    ConfigProperty {
+        name: FEDERATED_SEARCH_INDEX,
+        property_type: PropertyType::PATH,
+        description: "The directory of the search index spanning all the workspaces",
+        range: None,
+    },
+    ConfigProperty {
+        name: SEARCH_AUTO_REFRESH,
+        property_type: PropertyType::BOOLEAN,
+        description:
+            "Update the search index before searching if the Decision Records have changed",
+        range: None,
+    },
+    ConfigProperty {
+        name: ADR_EXTENSIONS,
+        property_type: PropertyType::LIST,
+        description: "The extensions of the Decision Records files",
+        range: None,
+    },
+    ConfigProperty {
+        name: ADR_INCLUDE,
+        property_type: PropertyType::LIST,
+        description: "The globs the Decision Records files must match (all the files when empty)",
+        range: None,
+    },
+    ConfigProperty {
+        name: ADR_EXCLUDE,
+        property_type: PropertyType::LIST,
+        description: "The globs of the files which are not Decision Records (cf. .adrignore)",
+        range: None,
+    },
+];
+
+/// Returns the definition of the property, or an error if it is unknown
+pub fn get_property(name: &str) -> std::result::Result<&'static ConfigProperty, String> {
+    match CONFIG_PROPERTIES
+        .iter()
+        .find(|property| property.name == name)
+    {
+        Some(property) => Ok(property),
+        None => Err(format!("Unknown property [{}] - cf. adr config list", name)),
+    }
+}
+
+/// Returns the value of the property in the configuration, as listed by `adr config list`
+pub fn get_property_value(cfg: &AdrToolConfig, name: &str) -> std::result::Result<String, String> {
+    let property = get_property(name)?;
+    match to_properties(cfg)?.get(property.name) {
+        Some(toml::Value::String(value)) => Ok(value.clone()),
+        Some(toml::Value::Array(values)) => Ok(format!("{} item(s)", values.len())),
+        Some(value) => Ok(value.to_string()),
+        None => Err(format!("Unknown property [{}]", name)),
+    }
+}
+
+fn to_properties(cfg: &AdrToolConfig) -> std::result::Result<toml::value::Table, String> {
+    match toml::Value::try_from(cfg) {
+        Ok(toml::Value::Table(table)) => Ok(table),
+        Ok(_) => Err(String::from("Unable to read the configuration")),
+        Err(why) => Err(why.to_string()),
+    }
+}
+
+/// The name of the repository-local configuration file, cf. `find_local_config`
+pub const LOCAL_CONFIG_FILE: &str = ".adrust.toml";
+
+/// The environment variable giving the local configuration file to use instead of the `.adrust.toml` found from the
+/// current directory
+pub const ENV_CONFIG: &str = "ADRUST_CONFIG";
+
+/// The prefix of the environment variables overriding the properties, cf. `env_var_name`
+pub const ENV_PREFIX: &str = "ADRUST_";
+
+static CONFIG_WARNING: Once = Once::new();
+
+lazy_static! {
+    static ref CLI_OVERRIDES: RwLock<CliOverrides> = RwLock::new(CliOverrides::default());
+}
+
+/// The overrides given on the command line, cf. `set_cli_overrides`
+#[derive(Debug, Clone, Default)]
+struct CliOverrides {
+    config: Option<PathBuf>,
+    properties: Vec<(String, String)>,
+}
+
+/// The layer a property of the configuration comes from, from the lowest to the highest precedence
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ConfigSource {
+    /// the default value, when there is no global configuration
+    DEFAULT,
+    /// the global configuration, cf. `get_config_from_name`
+    GLOBAL,
+    /// the local configuration, cf. `find_local_config`
+    LOCAL,
+    /// the current workspace, cf. `WorkspaceDefinition`
+    WORKSPACE,
+    /// an environment variable, cf. `env_var_name`
+    ENV,
+    /// the command line, cf. `set_cli_overrides`
+    CLI,
+}
+
+impl ConfigSource {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ConfigSource::DEFAULT => "default",
+            ConfigSource::GLOBAL => "global",
+            ConfigSource::LOCAL => "local",
+            ConfigSource::WORKSPACE => "workspace",
+            ConfigSource::ENV => "env",
+            ConfigSource::CLI => "cli",
+        }
+    }
+}
+
+/// The effective configuration, with the layer each property comes from
+#[derive(Debug, Clone)]
+pub struct ConfigLayers {
+    pub config: AdrToolConfig,
+    /// The layer of each property, by property name (e.g. `adr_src_dir`)
+    pub sources: BTreeMap<String, ConfigSource>,
+    /// The path of the local configuration, if any
+    pub local_config: Option<PathBuf>,
+    /// The overrides which have been ignored, e.g. an invalid value
+    pub errors: Vec<String>,
+}
+
+fn default_wip_max_age_days() -> u32 {
+    90
+}
 
 impl ::std::default::Default for AdrToolConfig {
     fn default() -> Self {
         AdrToolConfig {
+            version: CONFIG_VERSION,
             //adr_root_dir: "/tmp/adr-samples".to_string(),//irrelevant ? following murex convention, it seems more natural to keep adr_root_dir than adr_scr_dir (cf. adr_template_dir)
             adr_src_dir: "/tmp/adr-samples/src".to_string(), //"npryce convention :  doc/adr; murex convention : docs/adr"
             adr_template_dir: "/tmp/adr-samples/templates".to_string(), //"npryce convention : src; murex convention : docs/adr/templates"
@@ -36,31 +387,33 @@ impl ::std::default::Default for AdrToolConfig {
             log_level: 4, //info
             use_id_prefix: true,
             id_prefix_width: 6,
+            wip_max_age_days: default_wip_max_age_days(),
+            review_quorum: 0,
+            auto_commit: false,
+            allowed_tags: Vec::new(),
+            workspace: String::new(),
+            workspaces: Vec::new(),
+            federated_search_index: "/tmp/adr-samples/.federated-index".to_string(),
+            search_auto_refresh: true,
+            adr_extensions: vec![String::from("adoc")],
+            adr_include: Vec::new(),
+            adr_exclude: Vec::new(),
         }
     }
 }
 
 fn get_logger() -> slog::Logger {
-    let cfg: AdrToolConfig = get_config();
-
-    let decorator = slog_term::TermDecorator::new().build();
-    let drain = slog_term::FullFormat::new(decorator).build().fuse();
-    let drain = slog_async::Async::new(drain).build().fuse();
-    let drain = slog::LevelFilter::new(
-        drain,
-        Level::from_usize(cfg.log_level).unwrap_or(Level::Debug),
-    )
-    .fuse();
-
-    slog::Logger::root(drain, o!())
+    crate::logger::get_logger()
 }
 
 pub fn init() -> Result<()> {
     init_from_name("adrust-tools")
 }
 
+/// Creates the directories of the effective configuration (cf. `get_config_layers`), i.e. the ones of the current
+/// workspace if any
 pub fn init_from_name(config_name: &str) -> Result<()> {
-    let cfg: AdrToolConfig = get_config_from_name(config_name);
+    let cfg: AdrToolConfig = get_config_layers_from_name(config_name).config;
     // let path = cfg.adr_root_dir;
     // fs::create_dir_all(&path)?;
     // info!(get_logger(), "[{}] created]", path);
@@ -100,18 +453,407 @@ pub fn init_from_name(config_name: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn set_config(name: &str, value: &str) -> Result<()> {
+pub fn set_config(name: &str, value: &str) -> io::Result<()> {
     set_config_from_name("adrust-tools", name, value)
 }
 
+pub fn unset_config(name: &str) -> io::Result<()> {
+    unset_config_from_name("adrust-tools", name)
+}
+
+pub fn reset_config() -> io::Result<()> {
+    reset_config_from_name("adrust-tools")
+}
+
+/// Returns the configuration, cf. `get_config_layers`. The overrides which cannot be applied are reported once.
 pub fn get_config() -> AdrToolConfig {
-    get_config_from_name("adrust-tools")
+    let layers = get_config_layers_from_name("adrust-tools");
+    if !layers.errors.is_empty() {
+        //not logged, as the logger needs the configuration
+        CONFIG_WARNING.call_once(|| {
+            for error in layers.errors.iter() {
+                eprintln!("{}", error);
+            }
+        });
+    }
+
+    layers.config
+}
+
+/// Returns the configuration and the layer each property comes from. From the lowest to the highest precedence: the
+/// global configuration (or the default one), the local configuration (`--config`, `ADRUST_CONFIG` or the
+/// `.adrust.toml` found from the current directory), the current workspace (cf. `apply_workspace`), the environment
+/// variables (e.g. `ADRUST_SRC_DIR`) and the command line (e.g. `--src-dir`).
+pub fn get_config_layers() -> ConfigLayers {
+    get_config_layers_from_name("adrust-tools")
+}
+
+pub fn get_config_layers_from_name(config: &str) -> ConfigLayers {
+    //no logging here, as the logger needs the configuration
+    let mut errors = Vec::new();
+    let (cfg, global_source) = match load_config_from_name(config) {
+        Ok(Some(cfg)) => (cfg, ConfigSource::GLOBAL),
+        Ok(None) => (AdrToolConfig::default(), ConfigSource::DEFAULT),
+        Err(why) => {
+            errors.push(why);
+            (AdrToolConfig::default(), ConfigSource::DEFAULT)
+        }
+    };
+    let mut properties = match toml::Value::try_from(cfg.clone()) {
+        Ok(toml::Value::Table(table)) => table,
+        _ => toml::value::Table::new(),
+    };
+    let mut layers = ConfigLayers {
+        config: cfg,
+        sources: properties
+            .keys()
+            .map(|key| (key.clone(), global_source))
+            .collect(),
+        local_config: None,
+        errors,
+    };
+    let cli_overrides = match CLI_OVERRIDES.read() {
+        Ok(cli_overrides) => cli_overrides.clone(),
+        Err(_why) => CliOverrides::default(),
+    };
+    let current_dir = std::env::current_dir().unwrap_or_else(|_why| PathBuf::from("."));
+
+    layers.local_config = match (&cli_overrides.config, std::env::var_os(ENV_CONFIG)) {
+        (Some(path), _) => Some(current_dir.join(path)),
+        (None, Some(path)) => Some(current_dir.join(path)),
+        (None, None) => find_local_config(&current_dir),
+    };
+    if let Some(path) = &layers.local_config {
+        match read_local_config(&properties, path) {
+            Ok(local) => {
+                for (key, value) in local {
+                    layers.sources.insert(key.clone(), ConfigSource::LOCAL);
+                    properties.insert(key, value);
+                }
+            }
+            Err(why) => layers
+                .errors
+                .push(format!("Ignoring [{}] - {}", path.display(), why)),
+        }
+    }
+
+    apply_env_overrides(&mut layers, &mut properties, &current_dir, |name| {
+        std::env::var(name).ok()
+    });
+
+    for (key, value) in cli_overrides.properties.iter() {
+        match set_property(&mut properties, key, value, &current_dir) {
+            Ok(()) => {
+                layers.sources.insert(key.clone(), ConfigSource::CLI);
+            }
+            Err(why) => layers.errors.push(format!(
+                "Ignoring [{}] given on the command line - {}",
+                key, why
+            )),
+        }
+    }
+
+    match toml::Value::Table(properties).try_into() {
+        Ok(cfg) => layers.config = cfg,
+        Err(why) => {
+            let why: toml::de::Error = why;
+            layers
+                .errors
+                .push(format!("Ignoring the overrides - {}", why))
+        }
+    }
+    if let Err(why) = apply_workspace(&mut layers) {
+        layers.errors.push(why);
+    }
+
+    layers
+}
+
+/// Replaces the directories of the configuration with the ones of the current workspace (`workspace`, which may be
+/// given by `ADRUST_WORKSPACE` or `--workspace`). The directories given by the local configuration, an environment
+/// variable or on the command line are kept.
+fn apply_workspace(layers: &mut ConfigLayers) -> std::result::Result<(), String> {
+    if layers.config.workspace.is_empty() {
+        return Ok(());
+    }
+    let workspace = get_workspace(&layers.config, &layers.config.workspace)?.clone();
+
+    let sources = &mut layers.sources;
+    let mut apply = |key: &str, field: &mut String, value: String| {
+        match sources.get(key) {
+            Some(ConfigSource::LOCAL) | Some(ConfigSource::ENV) | Some(ConfigSource::CLI) => (),
+            _ => {
+                *field = value;
+                sources.insert(String::from(key), ConfigSource::WORKSPACE);
+            }
+        };
+    };
+    let cfg = &mut layers.config;
+    apply(ADR_SRC_DIR, &mut cfg.adr_src_dir, workspace.adr_src_dir);
+    apply(
+        ADR_TEMPLATE_DIR,
+        &mut cfg.adr_template_dir,
+        workspace.adr_template_dir,
+    );
+    apply(
+        ADR_SEARCH_INDEX,
+        &mut cfg.adr_search_index,
+        workspace.adr_search_index,
+    );
+
+    Ok(())
+}
+
+/// Returns the workspace of the configuration, or an error if it is unknown
+pub fn get_workspace<'a>(
+    cfg: &'a AdrToolConfig,
+    name: &str,
+) -> std::result::Result<&'a WorkspaceDefinition, String> {
+    match cfg
+        .workspaces
+        .iter()
+        .find(|workspace| workspace.name == name)
+    {
+        Some(workspace) => Ok(workspace),
+        None => Err(format!(
+            "Unknown workspace [{}] - cf. adr workspace list",
+            name
+        )),
+    }
+}
+
+pub fn add_workspace(workspace: WorkspaceDefinition) -> io::Result<()> {
+    add_workspace_from_name("adrust-tools", workspace)
+}
+
+pub fn use_workspace(name: &str) -> io::Result<()> {
+    use_workspace_from_name("adrust-tools", name)
+}
+
+/// Adds a workspace to the global configuration. Its relative directories are relative to the current directory.
+///
+/// # Arguments
+///
+/// * `config` - the name of the configuration, cf. `get_config_path`
+/// * `workspace` - the workspace, whose name must not be used yet
+///
+pub fn add_workspace_from_name(config: &str, workspace: WorkspaceDefinition) -> io::Result<()> {
+    if workspace.name.trim().is_empty() {
+        return Err(invalid_input(String::from(
+            "The name of the workspace cannot be empty",
+        )));
+    }
+    let mut cfg: AdrToolConfig = load_config_to_update(config)?;
+    if get_workspace(&cfg, &workspace.name).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("Workspace [{}] already exists", workspace.name),
+        ));
+    }
+
+    let current_dir = std::env::current_dir()?;
+    let resolve = |path: &str| {
+        resolve_path(&current_dir, path)
+            .to_string_lossy()
+            .into_owned()
+    };
+    cfg.workspaces.push(WorkspaceDefinition {
+        name: workspace.name.clone(),
+        adr_src_dir: resolve(&workspace.adr_src_dir),
+        adr_template_dir: resolve(&workspace.adr_template_dir),
+        adr_search_index: resolve(&workspace.adr_search_index),
+    });
+
+    store_config(config, &cfg)
+}
+
+/// Makes the workspace the current one in the global configuration
+pub fn use_workspace_from_name(config: &str, name: &str) -> io::Result<()> {
+    let mut cfg: AdrToolConfig = load_config_to_update(config)?;
+    get_workspace(&cfg, name).map_err(invalid_input)?;
+    cfg.workspace = String::from(name);
+
+    store_config(config, &cfg)
+}
+
+/// Sets the overrides given on the command line, which take precedence over all the other layers of the
+/// configuration (cf. `get_config_layers`) for the rest of the process.
+///
+/// # Arguments
+///
+/// * `config` - the local configuration file to use instead of the `.adrust.toml` found from the current directory
+/// * `properties` - the properties, by name (e.g. `adr_src_dir`), with their value
+///
+pub fn set_cli_overrides(config: Option<PathBuf>, properties: Vec<(String, String)>) {
+    if let Ok(mut cli_overrides) = CLI_OVERRIDES.write() {
+        *cli_overrides = CliOverrides { config, properties };
+    }
+}
+
+/// Returns the environment variable overriding the property, e.g. `ADRUST_SRC_DIR` for `adr_src_dir` or
+/// `ADRUST_LOG_LEVEL` for `log_level`
+pub fn env_var_name(property: &str) -> String {
+    let property = match property.starts_with("adr_") {
+        true => &property[4..],
+        false => property,
+    };
+    format!("{}{}", ENV_PREFIX, property.to_uppercase())
+}
+
+/// Overrides the properties by the environment variables (cf. `env_var_name`), as returned by `get_var`. Only the
+/// properties which can be set by `adr config set` can be overridden, e.g. not `version`.
+fn apply_env_overrides<F>(
+    layers: &mut ConfigLayers,
+    properties: &mut toml::value::Table,
+    current_dir: &Path,
+    get_var: F,
+) where
+    F: Fn(&str) -> Option<String>,
+{
+    for property in CONFIG_PROPERTIES
+        .iter()
+        .filter(|property| property.is_modifiable())
+    {
+        let name = env_var_name(property.name);
+        if let Some(value) = get_var(&name) {
+            match set_property(properties, property.name, &value, current_dir) {
+                Ok(()) => {
+                    layers
+                        .sources
+                        .insert(String::from(property.name), ConfigSource::ENV);
+                }
+                Err(why) => layers.errors.push(format!("Ignoring [{}] - {}", name, why)),
+            }
+        }
+    }
+}
+
+/// Sets the property from its textual value (as given by an environment variable or on the command line), according
+/// to the type of its current value
+fn set_property(
+    properties: &mut toml::value::Table,
+    key: &str,
+    value: &str,
+    current_dir: &Path,
+) -> std::result::Result<(), String> {
+    if let Ok(property) = get_property(key) {
+        property.validate(value)?;
+    }
+    let value = match properties.get(key) {
+        Some(toml::Value::String(_)) if is_path_property(key) => {
+            toml::Value::String(format!("{}", resolve_path(current_dir, value).display()))
+        }
+        Some(toml::Value::String(_)) => toml::Value::String(String::from(value)),
+        Some(toml::Value::Integer(_)) => match value.parse() {
+            Ok(value) => toml::Value::Integer(value),
+            Err(_why) => return Err(format!("[{}] is not a number", value)),
+        },
+        Some(toml::Value::Boolean(_)) => match value.parse() {
+            Ok(value) => toml::Value::Boolean(value),
+            Err(_why) => return Err(format!("[{}] is not a boolean", value)),
+        },
+        Some(_) => return Err(format!("Property [{}] cannot be overridden", key)),
+        None => return Err(format!("Unknown property [{}]", key)),
+    };
+    properties.insert(String::from(key), value);
+
+    Ok(())
 }
 
-pub fn set_config_from_name(config: &str, name: &str, value: &str) -> Result<()> {
+/// The paths are relative to the local configuration file (or to the current directory when given by `adr config set`,
+/// an environment variable or on the command line)
+fn is_path_property(key: &str) -> bool {
+    match get_property(key) {
+        Ok(property) => property.property_type == PropertyType::PATH,
+        Err(_why) => false,
+    }
+}
+
+/// Returns `path` relative to `base_dir` (or `path` itself if absolute)
+fn resolve_path(base_dir: &Path, path: &str) -> PathBuf {
+    let path: PathBuf = Path::new(path)
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .collect();
+    match path.as_os_str().is_empty() {
+        true => base_dir.to_path_buf(),
+        false => base_dir.join(path),
+    }
+}
+
+/// Returns the local configuration file (`.adrust.toml`) of `dir`, i.e. the first one found walking up from `dir`
+/// (typically the root of the repository).
+pub fn find_local_config(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join(LOCAL_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+/// Overrides `cfg` with the properties defined in the local configuration file `path`, key by key. The paths of the
+/// local configuration (`adr_src_dir`, `adr_template_dir` and `adr_search_index`) are relative to the file.
+///
+/// # Arguments
+///
+/// * `cfg` - the configuration to override, typically the global one
+/// * `path` - the local configuration file, cf. `find_local_config`
+///
+pub fn apply_local_config(
+    cfg: AdrToolConfig,
+    path: &Path,
+) -> std::result::Result<AdrToolConfig, String> {
+    let mut properties = match toml::Value::try_from(cfg) {
+        Ok(toml::Value::Table(table)) => table,
+        Ok(_) => return Err(String::from("Unable to read the configuration")),
+        Err(why) => return Err(why.to_string()),
+    };
+    for (key, value) in read_local_config(&properties, path)? {
+        properties.insert(key, value);
+    }
+
+    toml::Value::Table(properties)
+        .try_into()
+        .map_err(|why: toml::de::Error| why.to_string())
+}
+
+/// Reads the properties of the local configuration file `path`, checked against the known `properties`, with their
+/// paths resolved
+fn read_local_config(
+    properties: &toml::value::Table,
+    path: &Path,
+) -> std::result::Result<toml::value::Table, String> {
+    let content = fs::read_to_string(path).map_err(|why| why.to_string())?;
+    let mut local: toml::value::Table = toml::from_str(&content).map_err(|why| why.to_string())?;
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    for (key, value) in local.iter_mut() {
+        if !properties.contains_key(key) {
+            return Err(format!("Unknown property [{}]", key));
+        }
+        if let toml::Value::String(dir) = value {
+            if is_path_property(key) {
+                *dir = format!("{}", resolve_path(base_dir, dir).display());
+            }
+        }
+    }
+
+    Ok(local)
+}
+
+/// Sets the property of the global configuration. `adr_root_dir` sets the directories of the Decision Records, the
+/// templates and the search index at once. Unknown properties and invalid values are errors (cf. `ConfigProperty`).
+///
+/// # Arguments
+///
+/// * `config` - the name of the configuration, cf. `get_config_path`
+/// * `name` - the name of the property, e.g. `adr_src_dir`
+/// * `value` - the value of the property, relative to the current directory for the paths
+///
+pub fn set_config_from_name(config: &str, name: &str, value: &str) -> io::Result<()> {
     if ADR_ROOT_DIR == name {
+        if value.trim().is_empty() {
+            return Err(invalid_input(format!("[{}] cannot be empty", name)));
+        }
         //for now keep it to apply standard murex convention
-        let cfg: AdrToolConfig = get_config_from_name(config);
         let adr_src_dir = String::from(value);
         //adr_src_dir.push_str("/src");
 
@@ -119,71 +861,279 @@ pub fn set_config_from_name(config: &str, name: &str, value: &str) -> Result<()>
 
         let adr_search_index = Path::new(value).join(".index");
 
-        let new_cfg = AdrToolConfig {
-            //adr_root_dir: String::from(value),
-            adr_src_dir: adr_src_dir,
-            adr_template_dir: format!("{}", adr_template_dir.display()),
-            adr_template_file: cfg.adr_template_file,
-            adr_search_index: format!("{}", adr_search_index.display()),
-            log_level: cfg.log_level, //info
-            use_id_prefix: cfg.use_id_prefix,
-            id_prefix_width: cfg.id_prefix_width,
-        };
+        return update_config_from_name(config, |properties| {
+            properties.insert(
+                String::from("adr_src_dir"),
+                toml::Value::String(adr_src_dir),
+            );
+            properties.insert(
+                String::from("adr_template_dir"),
+                toml::Value::String(format!("{}", adr_template_dir.display())),
+            );
+            properties.insert(
+                String::from("adr_search_index"),
+                toml::Value::String(format!("{}", adr_search_index.display())),
+            );
+            Ok(())
+        });
+    }
+    let property = get_property(name).map_err(invalid_input)?;
+    let defaults = to_properties(&AdrToolConfig::default()).map_err(invalid_input)?;
+    let current_dir = std::env::current_dir()?;
+    update_config_from_name(config, |properties| {
+        //the value gets the type of the property, whatever the current (possibly invalid) one
+        if let Some(value) = defaults.get(property.name) {
+            properties.insert(String::from(property.name), value.clone());
+        }
+        set_property(properties, property.name, value, &current_dir).map_err(invalid_input)
+    })
+}
 
-        confy::store(config, new_cfg).unwrap();
+/// Sets the property of the global configuration back to its default value
+pub fn unset_config_from_name(config: &str, name: &str) -> io::Result<()> {
+    let property = get_property(name).map_err(invalid_input)?;
+    let defaults = to_properties(&AdrToolConfig::default()).map_err(invalid_input)?;
+    update_config_from_name(config, |properties| {
+        if let Some(value) = defaults.get(property.name) {
+            properties.insert(String::from(property.name), value.clone());
+        }
+        Ok(())
+    })
+}
+
+/// Updates the properties of the global configuration file as they are written, i.e. a property whose value is invalid
+/// can be fixed. The file is written only if the whole configuration is valid once updated.
+fn update_config_from_name<F>(config: &str, update: F) -> io::Result<()>
+where
+    F: FnOnce(&mut toml::value::Table) -> io::Result<()>,
+{
+    let path = match get_config_path(config) {
+        Some(path) => path,
+        None => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Unable to find the configuration directory",
+            ))
+        }
+    };
+    let mut properties = match read_config_file(&path) {
+        Ok(Some(properties)) => properties,
+        Ok(None) => to_properties(&AdrToolConfig::default()).map_err(invalid_input)?,
+        Err(why) => {
+            return Err(io::Error::new(
+                why.kind(),
+                format!(
+                    "Unable to read the configuration [{}] - {}",
+                    path.display(),
+                    why
+                ),
+            ))
+        }
+    };
+    migrate_config(&mut properties);
+
+    update(&mut properties)?;
+    if let Err(why) = from_properties(properties.clone()) {
+        return Err(invalid_input(format!(
+            "Unable to write the configuration [{}] - {}",
+            path.display(),
+            why
+        )));
     }
-    if ADR_SRC_DIR == name {
-        let mut cfg: AdrToolConfig = get_config_from_name(config);
-        cfg.adr_src_dir = String::from(value);
-        confy::store(config, cfg).unwrap();
+
+    write_config_file(&path, &properties)
+}
+
+/// Returns the global configuration to update, or the default one if there is none. A configuration which cannot be
+/// read is an error, so that it is not overwritten with the default values.
+fn load_config_to_update(config: &str) -> io::Result<AdrToolConfig> {
+    match load_config_from_name(config) {
+        Ok(Some(cfg)) => Ok(cfg),
+        Ok(None) => Ok(AdrToolConfig::default()),
+        Err(why) => Err(io::Error::new(io::ErrorKind::InvalidData, why)),
     }
-    if ADR_TEMPLATE_DIR == name {
-        let mut cfg: AdrToolConfig = get_config_from_name(config);
-        cfg.adr_template_dir = String::from(value);
-        confy::store(config, cfg).unwrap();
+}
+
+/// Sets all the properties of the global configuration back to their default value (the unknown ones are kept)
+pub fn reset_config_from_name(config: &str) -> io::Result<()> {
+    store_config(config, &AdrToolConfig::default())
+}
+
+/// Checks that the directory exists, or creates it if `create` is set
+pub fn check_dir(path: &Path, create: bool) -> io::Result<()> {
+    match (path.is_dir(), create) {
+        (true, _) => Ok(()),
+        (false, true) => fs::create_dir_all(path),
+        (false, false) => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "[{}] does not exist - use --create to create it",
+                path.display()
+            ),
+        )),
     }
-    if ADR_TEMPLATE_FILE == name {
-        let mut cfg: AdrToolConfig = get_config_from_name(config);
-        cfg.adr_template_file = String::from(value);
-        confy::store(config, cfg).unwrap();
+}
+
+fn from_properties(properties: toml::value::Table) -> io::Result<AdrToolConfig> {
+    toml::Value::Table(properties)
+        .try_into()
+        .map_err(|why: toml::de::Error| invalid_input(why.to_string()))
+}
+
+fn invalid_input(why: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, why)
+}
+
+/// Returns the global configuration, or the default one if there is none (or if it cannot be read, which is reported).
+pub fn get_config_from_name(config: &str) -> AdrToolConfig {
+    match load_config_from_name(config) {
+        Ok(Some(cfg)) => cfg,
+        Ok(None) => AdrToolConfig::default(),
+        Err(why) => {
+            eprintln!("{} - using the default configuration", why);
+            AdrToolConfig::default()
+        }
     }
-    if LOG_LEVEL == name {
-        let mut cfg: AdrToolConfig = get_config_from_name(config);
-        cfg.log_level = value.parse().unwrap();
-        confy::store(config, cfg).unwrap();
+}
+
+/// Returns the path of the global configuration file, e.g. `$HOME/.config/adrust-tools/adrust-tools.toml`
+pub fn get_config_path(config: &str) -> Option<PathBuf> {
+    ProjectDirs::from("rs", config, config)
+        .map(|dirs| dirs.config_dir().join(format!("{}.toml", config)))
+}
+
+/// Loads the global configuration, migrated to the current schema (cf. `migrate_config`). The migrated file is written
+/// back when possible. Returns `None` if there is no global configuration yet, and an error if it cannot be read.
+pub fn load_config_from_name(config: &str) -> std::result::Result<Option<AdrToolConfig>, String> {
+    let path = match get_config_path(config) {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    let mut properties = match read_config_file(&path) {
+        Ok(Some(properties)) => properties,
+        Ok(None) => return Ok(None),
+        Err(why) => {
+            return Err(format!(
+                "Unable to read the configuration [{}] - {}",
+                path.display(),
+                why
+            ))
+        }
+    };
+
+    if migrate_config(&mut properties) {
+        //best effort, the configuration directory may be read-only (e.g. in a CI)
+        let _result = write_config_file(&path, &properties);
     }
 
-    if USE_ID_PREFIX == name {
-        let mut cfg: AdrToolConfig = get_config_from_name(config);
-        cfg.use_id_prefix = value.parse().unwrap();
-        confy::store(config, cfg).unwrap();
+    match toml::Value::Table(properties).try_into() {
+        Ok(cfg) => Ok(Some(cfg)),
+        Err(why) => {
+            let why: toml::de::Error = why;
+            Err(format!(
+                "Unable to read the configuration [{}] - {}",
+                path.display(),
+                why
+            ))
+        }
     }
+}
 
-    if ID_PREFIX_WIDTH == name {
-        let mut cfg: AdrToolConfig = get_config_from_name(config);
-        cfg.id_prefix_width = value.parse().unwrap();
-        confy::store(config, cfg).unwrap();
+/// Migrates the properties of a configuration file written with an older schema, i.e. the properties added since are
+/// set with their default value. Unknown properties are kept, and a newer schema is left as is (its unknown properties
+/// being ignored). Returns `true` if the properties have been migrated.
+pub fn migrate_config(properties: &mut toml::value::Table) -> bool {
+    let version = match properties.get("version") {
+        Some(toml::Value::Integer(version)) => *version,
+        _ => 0,
+    };
+    if version >= CONFIG_VERSION as i64 {
+        return false;
     }
 
-    Ok(())
+    //0 -> 1: the properties added to AdrToolConfig (e.g. wip_max_age_days) get their default value
+    //1 -> 2: idem for workspace and workspaces
+    //2 -> 3: idem for federated_search_index
+    //3 -> 4: idem for adr_extensions, adr_include and adr_exclude
+    //4 -> 5: idem for search_auto_refresh
+    if let Ok(toml::Value::Table(defaults)) = toml::Value::try_from(AdrToolConfig::default()) {
+        for (key, value) in defaults {
+            properties.entry(key).or_insert(value);
+        }
+    }
+    properties.insert(
+        String::from("version"),
+        toml::Value::Integer(CONFIG_VERSION as i64),
+    );
+
+    true
 }
 
-pub fn get_config_from_name(config: &str) -> AdrToolConfig {
-    let cfg: AdrToolConfig = match confy::load(config) {
-        Err(_why) => {
-            warn!(get_logger(), "Returning default configuration file");
-            AdrToolConfig::default()
+/// Writes the global configuration, keeping the properties of the existing file which are unknown to this version
+pub fn store_config(config: &str, cfg: &AdrToolConfig) -> io::Result<()> {
+    let path = match get_config_path(config) {
+        Some(path) => path,
+        None => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Unable to find the configuration directory",
+            ))
+        }
+    };
+    let mut properties = read_config_file(&path)?.unwrap_or_default();
+    let version = properties.get("version").cloned();
+
+    let cfg = match toml::Value::try_from(cfg) {
+        Ok(toml::Value::Table(table)) => table,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Unable to write the configuration",
+            ))
+        }
+    };
+    for (key, value) in cfg {
+        properties.insert(key, value);
+    }
+    //a file written by a newer version keeps its version
+    if let Some(toml::Value::Integer(version)) = version {
+        if version > CONFIG_VERSION as i64 {
+            properties.insert(String::from("version"), toml::Value::Integer(version));
         }
-        Ok(e) => e,
+    }
+
+    write_config_file(&path, &properties)
+}
+
+fn read_config_file(path: &Path) -> io::Result<Option<toml::value::Table>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(ref why) if why.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(why) => return Err(why),
     };
+    match toml::from_str(&content) {
+        Ok(properties) => Ok(Some(properties)),
+        Err(why) => Err(io::Error::new(io::ErrorKind::InvalidData, why.to_string())),
+    }
+}
 
-    cfg
+fn write_config_file(path: &Path, properties: &toml::value::Table) -> io::Result<()> {
+    let content = match toml::to_string_pretty(&toml::Value::Table(properties.clone())) {
+        Ok(content) => content,
+        Err(why) => return Err(io::Error::new(io::ErrorKind::InvalidData, why.to_string())),
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, content)
 }
 
 #[cfg(test)]
 mod tests {
     use directories::ProjectDirs;
+    use std::collections::BTreeMap;
     use std::fs::{self};
+    use std::io;
     use std::path::Path;
     use uuid::*;
 
@@ -193,10 +1143,16 @@ mod tests {
         let name = format!("adrust-tools-4-tests-{}", uuid);
         let config = name.as_str();
 
-        super::set_config_from_name(config, "log_level", "7").unwrap();
+        super::set_config_from_name(config, "log_level", "6").unwrap();
         let cfg = super::get_config_from_name(config);
 
-        assert_eq!(cfg.log_level, 7);
+        assert_eq!(cfg.log_level, 6);
+        assert_eq!(
+            "[log_level] must be between [1] and [6]",
+            super::set_config_from_name(config, "log_level", "7")
+                .unwrap_err()
+                .to_string()
+        );
 
         teardown(config);
     }
@@ -229,22 +1185,354 @@ mod tests {
         teardown(config);
     }
 
+    #[test]
+    fn test_set_config_wip_max_age() {
+        let uuid = Uuid::new_v4();
+        let name = format!("adrust-tools-4-tests-{}", uuid);
+        let config = name.as_str();
+
+        let cfg = super::get_config_from_name(config);
+        assert_eq!(cfg.wip_max_age_days, 90);
+
+        super::set_config_from_name(config, "wip_max_age_days", "30").unwrap();
+        let cfg = super::get_config_from_name(config);
+
+        assert_eq!(cfg.wip_max_age_days, 30);
+
+        teardown(config);
+    }
+
+    #[test]
+    fn test_migrate_config() {
+        let mut properties: toml::value::Table =
+            toml::from_str("log_level = 7\nadr_src_dir = \"/adr\"\nmy_property = \"kept\"\n")
+                .unwrap();
+
+        assert!(super::migrate_config(&mut properties));
+        assert_eq!(
+            Some(&toml::Value::Integer(super::CONFIG_VERSION as i64)),
+            properties.get("version")
+        );
+        assert_eq!(
+            Some(&toml::Value::Integer(90)),
+            properties.get("wip_max_age_days")
+        );
+        assert_eq!(
+            Some(&toml::Value::String(String::from("kept"))),
+            properties.get("my_property")
+        );
+        let cfg: super::AdrToolConfig = toml::Value::Table(properties.clone()).try_into().unwrap();
+        assert_eq!(cfg.log_level, 7);
+        assert_eq!(cfg.adr_src_dir, "/adr");
+
+        assert!(!super::migrate_config(&mut properties));
+    }
+
+    #[test]
+    fn test_load_config_from_name() {
+        let uuid = Uuid::new_v4();
+        let name = format!("adrust-tools-4-tests-{}", uuid);
+        let config = name.as_str();
+        let path = super::get_config_path(config).unwrap();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        //a file written before the schema was versioned, with a property of a newer version
+        fs::write(&path, "log_level = 5\nmy_property = \"kept\"\n").unwrap();
+        let cfg = super::get_config_from_name(config);
+        assert_eq!(cfg.log_level, 5);
+        assert_eq!(cfg.wip_max_age_days, 90);
+        super::set_config_from_name(config, "review_quorum", "2").unwrap();
+        let properties: toml::value::Table =
+            toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(
+            Some(&toml::Value::Integer(super::CONFIG_VERSION as i64)),
+            properties.get("version")
+        );
+        assert_eq!(
+            Some(&toml::Value::String(String::from("kept"))),
+            properties.get("my_property")
+        );
+        assert_eq!(
+            Some(&toml::Value::Integer(2)),
+            properties.get("review_quorum")
+        );
+
+        fs::write(
+            &path,
+            "log_level = \"seven\"\nadr_src_dir = \"/tmp/my-adr\"\n",
+        )
+        .unwrap();
+        assert!(super::load_config_from_name(config).is_err());
+
+        //the configuration is not overwritten with the default values, but the invalid property can be fixed
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(super::set_config_from_name(config, "review_quorum", "2").is_err());
+        assert!(super::unset_config_from_name(config, "review_quorum").is_err());
+        assert!(super::use_workspace_from_name(config, "platform").is_err());
+        assert_eq!(content, fs::read_to_string(&path).unwrap());
+        super::set_config_from_name(config, "log_level", "4").unwrap();
+        let cfg = super::load_config_from_name(config).unwrap().unwrap();
+        assert_eq!(cfg.log_level, 4);
+        assert_eq!(cfg.adr_src_dir, "/tmp/my-adr");
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_env_var_name() {
+        assert_eq!("ADRUST_SRC_DIR", super::env_var_name("adr_src_dir"));
+        assert_eq!("ADRUST_LOG_LEVEL", super::env_var_name("log_level"));
+    }
+
+    #[test]
+    fn test_apply_env_overrides() {
+        let cfg = super::AdrToolConfig::default();
+        let mut properties = match toml::Value::try_from(cfg.clone()) {
+            Ok(toml::Value::Table(table)) => table,
+            _ => panic!("issue while preparing test"),
+        };
+        let mut layers = super::ConfigLayers {
+            config: cfg,
+            sources: BTreeMap::new(),
+            local_config: None,
+            errors: Vec::new(),
+        };
+        let mut vars = BTreeMap::new();
+        vars.insert("ADRUST_SRC_DIR", "docs/adr");
+        vars.insert("ADRUST_LOG_LEVEL", "ten");
+        vars.insert("ADRUST_VERSION", "1");
+        vars.insert("ADRUST_ALLOWED_TAGS", "security");
+        super::apply_env_overrides(
+            &mut layers,
+            &mut properties,
+            Path::new("/home/john/project"),
+            |name| vars.get(name).map(|value| String::from(*value)),
+        );
+
+        assert_eq!(
+            Some(&toml::Value::String(format!(
+                "{}",
+                Path::new("/home/john/project/docs/adr").display()
+            ))),
+            properties.get("adr_src_dir")
+        );
+        assert_eq!(
+            Some(&super::ConfigSource::ENV),
+            layers.sources.get("adr_src_dir")
+        );
+        assert_eq!(
+            vec![String::from(
+                "Ignoring [ADRUST_LOG_LEVEL] - [ten] is not a number"
+            )],
+            layers.errors
+        );
+        //only the properties which can be set can be overridden
+        assert_eq!(
+            Some(&toml::Value::Integer(
+                super::AdrToolConfig::default().version as i64
+            )),
+            properties.get("version")
+        );
+        assert_eq!(1, layers.sources.len());
+    }
+
+    #[test]
+    fn test_set_property() {
+        let mut properties = match toml::Value::try_from(super::AdrToolConfig::default()) {
+            Ok(toml::Value::Table(table)) => table,
+            _ => panic!("issue while preparing test"),
+        };
+        let current_dir = Path::new("/home/john/project");
+
+        super::set_property(&mut properties, "adr_src_dir", "docs/adr", current_dir).unwrap();
+        super::set_property(&mut properties, "log_level", "2", current_dir).unwrap();
+        super::set_property(&mut properties, "auto_commit", "true", current_dir).unwrap();
+        assert_eq!(
+            "[two] is not a number",
+            super::set_property(&mut properties, "log_level", "two", current_dir).unwrap_err()
+        );
+        assert!(super::set_property(&mut properties, "allowed_tags", "a", current_dir).is_err());
+
+        let cfg: super::AdrToolConfig = toml::Value::Table(properties).try_into().unwrap();
+        assert_eq!(
+            Path::new(&cfg.adr_src_dir),
+            Path::new("/home/john/project/docs/adr")
+        );
+        assert_eq!(cfg.log_level, 2);
+        assert!(cfg.auto_commit);
+    }
+
+    #[test]
+    fn test_apply_local_config() {
+        let root = std::env::temp_dir().join(format!("adrust-tools-4-tests-{}", Uuid::new_v4()));
+        let sub_dir = root.join("docs").join("adr");
+        fs::create_dir_all(&sub_dir).unwrap();
+        fs::write(
+            root.join(super::LOCAL_CONFIG_FILE),
+            "adr_src_dir = \"./docs/adr\"\nadr_search_index = \"/var/adr/.index\"\nreview_quorum = 2\n",
+        )
+        .unwrap();
+
+        let path = super::find_local_config(&sub_dir).unwrap();
+        assert_eq!(root.join(super::LOCAL_CONFIG_FILE), path);
+
+        let cfg = super::apply_local_config(super::AdrToolConfig::default(), &path).unwrap();
+        assert_eq!(Path::new(&cfg.adr_src_dir), sub_dir);
+        assert_eq!(cfg.adr_search_index, "/var/adr/.index");
+        assert_eq!(cfg.review_quorum, 2);
+        //the other properties are the global ones
+        assert_eq!(cfg.adr_template_dir, "/tmp/adr-samples/templates");
+        assert_eq!(cfg.id_prefix_width, 6);
+
+        fs::write(root.join(super::LOCAL_CONFIG_FILE), "adr_src = \"src\"\n").unwrap();
+        assert_eq!(
+            "Unknown property [adr_src]",
+            super::apply_local_config(super::AdrToolConfig::default(), &path).unwrap_err()
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_config_property_validate() {
+        let property = super::get_property("id_prefix_width").unwrap();
+        assert!(property.validate("6").is_ok());
+        assert_eq!("[-1] is not a number", property.validate("-1").unwrap_err());
+        assert_eq!(
+            "[id_prefix_width] must be between [1] and [32]",
+            property.validate("0").unwrap_err()
+        );
+        assert!(super::get_property("auto_commit")
+            .unwrap()
+            .validate("yes")
+            .is_err());
+        assert!(super::get_property("allowed_tags")
+            .unwrap()
+            .validate("a")
+            .is_err());
+        assert_eq!(
+            "Unknown property [adr_src] - cf. adr config list",
+            super::get_property("adr_src").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_set_config_errors() {
+        let uuid = Uuid::new_v4();
+        let name = format!("adrust-tools-4-tests-{}", uuid);
+        let config = name.as_str();
+
+        assert!(super::set_config_from_name(config, "adr_src", "/tmp").is_err());
+        assert!(super::set_config_from_name(config, "review_quorum", "two").is_err());
+        super::set_config_from_name(config, "adr_search_index", "/tmp/my-index").unwrap();
+        super::set_config_from_name(config, "review_quorum", "2").unwrap();
+        let cfg = super::get_config_from_name(config);
+        assert_eq!(cfg.adr_search_index, "/tmp/my-index");
+        assert_eq!(
+            "2",
+            super::get_property_value(&cfg, "review_quorum").unwrap()
+        );
+
+        super::unset_config_from_name(config, "review_quorum").unwrap();
+        let cfg = super::get_config_from_name(config);
+        assert_eq!(cfg.review_quorum, 0);
+        assert_eq!(cfg.adr_search_index, "/tmp/my-index");
+
+        super::reset_config_from_name(config).unwrap();
+        let cfg = super::get_config_from_name(config);
+        assert_eq!(cfg.adr_search_index, "/tmp/adr-samples/.index");
+
+        teardown(config);
+    }
+
+    #[test]
+    fn test_apply_workspace() {
+        let cfg = super::AdrToolConfig {
+            workspaces: vec![super::WorkspaceDefinition {
+                name: String::from("platform"),
+                adr_src_dir: String::from("/adr/platform/src"),
+                adr_template_dir: String::from("/adr/platform/templates"),
+                adr_search_index: String::from("/adr/platform/.index"),
+            }],
+            ..super::AdrToolConfig::default()
+        };
+        let mut layers = super::ConfigLayers {
+            config: cfg,
+            sources: BTreeMap::new(),
+            local_config: None,
+            errors: Vec::new(),
+        };
+
+        //no current workspace
+        super::apply_workspace(&mut layers).unwrap();
+        assert_eq!(layers.config.adr_src_dir, "/tmp/adr-samples/src");
+
+        layers.config.workspace = String::from("platform");
+        layers
+            .sources
+            .insert(String::from("adr_search_index"), super::ConfigSource::CLI);
+        layers
+            .sources
+            .insert(String::from("adr_template_dir"), super::ConfigSource::LOCAL);
+        super::apply_workspace(&mut layers).unwrap();
+        assert_eq!(layers.config.adr_src_dir, "/adr/platform/src");
+        //the local configuration and the command line take precedence
+        assert_eq!(layers.config.adr_template_dir, "/tmp/adr-samples/templates");
+        assert_eq!(layers.config.adr_search_index, "/tmp/adr-samples/.index");
+        assert_eq!(
+            Some(&super::ConfigSource::WORKSPACE),
+            layers.sources.get("adr_src_dir")
+        );
+
+        layers.config.workspace = String::from("product");
+        assert_eq!(
+            "Unknown workspace [product] - cf. adr workspace list",
+            super::apply_workspace(&mut layers).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn test_add_use_workspace() {
+        let uuid = Uuid::new_v4();
+        let name = format!("adrust-tools-4-tests-{}", uuid);
+        let config = name.as_str();
+
+        let workspace = super::WorkspaceDefinition {
+            name: String::from("platform"),
+            adr_src_dir: String::from("/adr/platform/src"),
+            adr_template_dir: String::from("/adr/platform/templates"),
+            adr_search_index: String::from("/adr/platform/.index"),
+        };
+        assert!(super::use_workspace_from_name(config, "platform").is_err());
+        super::add_workspace_from_name(config, workspace.clone()).unwrap();
+        assert_eq!(
+            io::ErrorKind::AlreadyExists,
+            super::add_workspace_from_name(config, workspace.clone())
+                .unwrap_err()
+                .kind()
+        );
+        super::use_workspace_from_name(config, "platform").unwrap();
+
+        let cfg = super::get_config_from_name(config);
+        assert_eq!(cfg.workspace, "platform");
+        assert_eq!(vec![workspace], cfg.workspaces);
+
+        teardown(config);
+    }
+
     fn teardown(name: &str) {
         println!("Want to delete folders [{:?}]", name);
-        //delete confy files
-        if let Some(dir) = ProjectDirs::from("rs", name, "") {
-            if dir.config_dir().exists() {
-                let dir = dir.config_dir().to_str().unwrap_or_default();
+        //delete the configuration directory of the test only
+        if let Some(path) = super::get_config_path(name) {
+            if let Some(dir) = path.parent() {
                 match fs::remove_dir_all(dir) {
                     Ok(_val) => {
                         println!("deleted test folders [{:?}]", dir);
                     }
                     Err(_why) => {
-                        println!("Problem while deleting test folder");
+                        println!("Problem while deleting test folder [{:?}]", dir);
                     }
                 }
-            } else {
-                println!("Unable to delete folder [{:?}]", dir.config_dir());
             }
         }
     }
@@ -1,5 +1,8 @@
-extern crate confy;
+extern crate directories;
+#[macro_use]
+extern crate lazy_static;
 #[macro_use]
 extern crate serde_derive;
 
 pub mod config;
+pub mod logger;
@@ -0,0 +1,199 @@
+use std::fs::OpenOptions;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
+
+extern crate slog;
+extern crate slog_json;
+extern crate slog_term;
+use slog::*;
+
+use crate::config::{get_config, AdrToolConfig};
+
+lazy_static! {
+    static ref LOGGER: RwLock<Option<slog::Logger>> = RwLock::new(None);
+}
+
+/// The format of the logs
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum LogFormat {
+    TEXT,
+    /// one JSON object per line, e.g. to be processed by a log collector
+    JSON,
+}
+
+impl LogFormat {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            LogFormat::TEXT => "text",
+            LogFormat::JSON => "json",
+        }
+    }
+}
+
+impl std::str::FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(val: &str) -> std::result::Result<LogFormat, String> {
+        match val {
+            "text" => Ok(LogFormat::TEXT),
+            "json" => Ok(LogFormat::JSON),
+            _ => Err(format!(
+                "Unknown log format [{}] - expected text or json",
+                val
+            )),
+        }
+    }
+}
+
+/// The settings of the logs, given once by the command line, cf. `init`
+#[derive(Debug, Clone)]
+pub struct LogSettings {
+    /// From 1 (critical) to 6 (trace), cf. `AdrToolConfig.log_level`
+    pub level: usize,
+    pub format: LogFormat,
+    /// The file the logs are appended to, instead of the terminal
+    pub file: Option<PathBuf>,
+}
+
+impl LogSettings {
+    /// The settings of the configuration: its log level, as text, in the terminal
+    pub fn from_config(cfg: &AdrToolConfig) -> LogSettings {
+        LogSettings {
+            level: cfg.log_level,
+            format: LogFormat::TEXT,
+            file: None,
+        }
+    }
+}
+
+/// Returns the log level given by `-v` and `-q` (each occurrence being one level more or less verbose), between 1
+/// (critical) and 6 (trace)
+///
+/// # Example
+///
+/// ```
+/// use adr_config::logger::get_verbosity;
+///
+/// assert_eq!(6, get_verbosity(4, 2, 0));
+/// assert_eq!(1, get_verbosity(4, 0, 5));
+/// ```
+pub fn get_verbosity(level: usize, verbose: u64, quiet: u64) -> usize {
+    let level = level as i64 + verbose as i64 - quiet as i64;
+    match level {
+        level if level < 1 => 1,
+        level if level > 6 => 6,
+        level => level as usize,
+    }
+}
+
+/// Sets the logger used by all the crates for the rest of the process (cf. `get_logger`)
+pub fn init(settings: &LogSettings) -> io::Result<()> {
+    let logger = build_logger(settings)?;
+    if let Ok(mut global) = LOGGER.write() {
+        *global = Some(logger);
+    }
+
+    Ok(())
+}
+
+/// Returns the logger set by `init`, or the one of the configuration if there is none yet
+pub fn get_logger() -> slog::Logger {
+    if let Ok(global) = LOGGER.read() {
+        if let Some(logger) = global.as_ref() {
+            return logger.clone();
+        }
+    }
+
+    //the logger of the configuration cannot fail, as it writes to the terminal
+    let settings = LogSettings::from_config(&get_config());
+    let logger = build_logger(&settings).unwrap_or_else(|_why| discard());
+    if let Ok(mut global) = LOGGER.write() {
+        *global = Some(logger.clone());
+    }
+    logger
+}
+
+/// Builds a logger from the settings. The logs are written synchronously, so that none is lost when the process
+/// exits.
+pub fn build_logger(settings: &LogSettings) -> io::Result<slog::Logger> {
+    let level = Level::from_usize(settings.level).unwrap_or(Level::Debug);
+    let file = match &settings.file {
+        Some(path) => Some(OpenOptions::new().create(true).append(true).open(path)?),
+        None => None,
+    };
+
+    let logger = match (settings.format, file) {
+        (LogFormat::TEXT, None) => {
+            let decorator = slog_term::TermDecorator::new().build();
+            root(slog_term::FullFormat::new(decorator).build(), level)
+        }
+        (LogFormat::TEXT, Some(file)) => {
+            let decorator = slog_term::PlainSyncDecorator::new(file);
+            root(slog_term::FullFormat::new(decorator).build(), level)
+        }
+        (LogFormat::JSON, None) => root(
+            slog_json::Json::new(io::stderr())
+                .add_default_keys()
+                .build(),
+            level,
+        ),
+        (LogFormat::JSON, Some(file)) => {
+            root(slog_json::Json::new(file).add_default_keys().build(), level)
+        }
+    };
+
+    Ok(logger)
+}
+
+fn root<D>(drain: D, level: Level) -> slog::Logger
+where
+    D: Drain + Send + 'static,
+    D::Err: std::fmt::Debug,
+{
+    let drain = Mutex::new(drain).fuse();
+    let drain = slog::LevelFilter::new(drain, level).fuse();
+
+    slog::Logger::root(drain, o!())
+}
+
+fn discard() -> slog::Logger {
+    slog::Logger::root(slog::Discard, o!())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::logger::*;
+    use std::fs;
+    use uuid::*;
+
+    #[test]
+    fn test_build_logger_json_file() {
+        let path =
+            std::env::temp_dir().join(format!("adrust-tools-4-tests-{}.log", Uuid::new_v4()));
+        let settings = LogSettings {
+            level: 4,
+            format: LogFormat::JSON,
+            file: Some(path.clone()),
+        };
+
+        let logger = build_logger(&settings).unwrap();
+        info!(logger, "Got [{}] ADR(s)", 2);
+        debug!(logger, "Not logged");
+
+        let content = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(1, lines.len());
+        let line: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!("Got [2] ADR(s)", line["msg"]);
+        assert_eq!("INFO", line["level"]);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_log_format_from_str() {
+        assert_eq!(Ok(LogFormat::JSON), "json".parse::<LogFormat>());
+        assert!("xml".parse::<LogFormat>().is_err());
+    }
+}
@@ -0,0 +1,264 @@
+extern crate slog;
+use slog::*;
+
+use std::collections::BTreeMap;
+use std::fs::{self};
+use std::io::{self};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+extern crate adr_config;
+use adr_config::config::AdrToolConfig;
+
+use crate::adr_repo::{split_path, Adr};
+
+fn get_logger() -> slog::Logger {
+    adr_config::logger::get_logger()
+}
+
+/// The version of the format of the cache. A cache with another version is ignored and rebuilt.
+const CACHE_VERSION: usize = 1;
+
+/// The parsed metadata of the ADRs of a directory, keyed by the path of their file in the directory, cf. `adr_repo::list_all_adr`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AdrCache {
+    version: usize,
+    dir: String,
+    entries: BTreeMap<String, CachedAdr>,
+}
+
+/// The metadata of an ADR, valid as long as its file has the same modification time and size
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedAdr {
+    modified_secs: u64,
+    modified_nanos: u32,
+    size: u64,
+    adr: Adr,
+}
+
+impl CachedAdr {
+    fn new(adr: Adr, metadata: &fs::Metadata) -> CachedAdr {
+        let (modified_secs, modified_nanos) = get_modified(metadata);
+        CachedAdr {
+            modified_secs,
+            modified_nanos,
+            size: metadata.len(),
+            adr,
+        }
+    }
+
+    fn is_fresh(&self, metadata: &fs::Metadata) -> bool {
+        (self.modified_secs, self.modified_nanos) == get_modified(metadata)
+            && self.size == metadata.len()
+    }
+}
+
+impl AdrCache {
+    /// An empty cache of the ADRs of `dir`
+    pub fn new(dir: &Path) -> AdrCache {
+        AdrCache {
+            version: CACHE_VERSION,
+            dir: format!("{}", dir.display()),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the metadata of the ADR (without its content), if its file has not changed since it has been cached
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - the directory of the ADRs, which becomes the `base_path` of the ADR
+    /// * `path` - the path of the file of the ADR
+    /// * `metadata` - the current metadata of the file
+    ///
+    pub fn get(&self, dir: &Path, path: &Path, metadata: &fs::Metadata) -> Option<Adr> {
+        match self.entries.get(&get_key(split_path(dir, path).1)) {
+            Some(cached) if cached.is_fresh(metadata) => {
+                let mut adr = cached.adr.clone();
+                adr.base_path = format!("{}", dir.display());
+                Some(adr)
+            }
+            _ => None,
+        }
+    }
+
+    /// Caches the metadata of the ADR, parsed from its file whose metadata is `metadata`
+    pub fn insert(&mut self, adr: &Adr, metadata: &fs::Metadata) {
+        let mut cached = adr.clone();
+        cached.content = String::new();
+        self.entries.insert(
+            get_key(Path::new(&adr.file_path)),
+            CachedAdr::new(cached, metadata),
+        );
+    }
+
+    /// Keeps only the ADRs of `adrs` (i.e. the ones whose file still exists). Returns true if some have been removed.
+    pub fn retain(&mut self, adrs: &[Adr]) -> bool {
+        let keys: Vec<String> = adrs
+            .iter()
+            .map(|adr| get_key(Path::new(&adr.file_path)))
+            .collect();
+        let len = self.entries.len();
+        self.entries.retain(|key, _cached| keys.contains(key));
+
+        len != self.entries.len()
+    }
+}
+
+fn get_key(path: &Path) -> String {
+    format!("{}", path.display())
+}
+
+fn get_modified(metadata: &fs::Metadata) -> (u64, u32) {
+    match metadata
+        .modified()
+        .map(|time| time.duration_since(UNIX_EPOCH))
+    {
+        Ok(Ok(duration)) => (duration.as_secs(), duration.subsec_nanos()),
+        _ => (0, 0),
+    }
+}
+
+/// Returns the path of the cache of the ADRs of `dir`: a file next to the search index of the directory (the one of
+/// the configuration or of the workspace whose directory is `dir`), or `None` if the directory has no search index.
+///
+/// # Arguments
+///
+/// * `cfg` - The whole config object
+/// * `dir` - the directory of the ADRs
+///
+pub fn get_cache_path(cfg: &AdrToolConfig, dir: &Path) -> Option<PathBuf> {
+    let search_index = match Path::new(&cfg.adr_src_dir) == dir {
+        true => Some(cfg.adr_search_index.as_str()),
+        false => cfg
+            .workspaces
+            .iter()
+            .find(|workspace| Path::new(&workspace.adr_src_dir) == dir)
+            .map(|workspace| workspace.adr_search_index.as_str()),
+    };
+
+    match search_index {
+        Some(search_index) if !search_index.is_empty() => {
+            let search_index = Path::new(search_index);
+            match (search_index.parent(), search_index.file_name()) {
+                (Some(parent), Some(name)) => {
+                    Some(parent.join(format!("{}.cache.json", name.to_string_lossy())))
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Loads the cache of the ADRs of `dir`. The cache is empty if the file does not exist, cannot be read or is not the
+/// one of `dir`.
+pub fn load_cache(path: &Path, dir: &Path) -> AdrCache {
+    let cache = fs::read_to_string(path)
+        .map_err(|why| format!("{}", why))
+        .and_then(|content| {
+            serde_json::from_str::<AdrCache>(&content).map_err(|why| format!("{}", why))
+        });
+
+    match cache {
+        Ok(cache) if cache.version == CACHE_VERSION && Path::new(&cache.dir) == dir => {
+            debug!(
+                get_logger(),
+                "Got [{}] cached ADR(s) from [{}]",
+                cache.entries.len(),
+                path.display()
+            );
+            cache
+        }
+        Ok(_cache) => {
+            debug!(get_logger(), "Ignoring cache [{}]", path.display());
+            AdrCache::new(dir)
+        }
+        Err(why) => {
+            debug!(
+                get_logger(),
+                "Unable to read cache [{}] - [{}]",
+                path.display(),
+                why
+            );
+            AdrCache::new(dir)
+        }
+    }
+}
+
+/// Writes the cache. The directory of the cache is not created, as it is the one of the search index.
+pub fn store_cache(path: &Path, cache: &AdrCache) -> io::Result<()> {
+    let content = serde_json::to_string(cache)?;
+    fs::write(path, content)?;
+    debug!(
+        get_logger(),
+        "Cached [{}] ADR(s) to [{}]",
+        cache.entries.len(),
+        path.display()
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adr_cache::*;
+    use crate::adr_repo::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_list_all_adr_with_cache() {
+        let dir = match TempDir::new("adr_cache") {
+            Ok(dir) => dir,
+            Err(why) => {
+                println!("{:?}", why);
+                panic!("{}", why);
+            }
+        };
+        let src = dir.path().join("src");
+        fs::create_dir_all(&src).unwrap();
+        let cache_path = dir.path().join(".index.cache.json");
+        let first = src.join("0001-use-kafka.adoc");
+        fs::write(
+            &first,
+            "= Use Kafka\n\n*Status:* {wip} *Date:* 2020-01-01\n",
+        )
+        .unwrap();
+        fs::write(
+            src.join("0002-use-rust.adoc"),
+            "= Use Rust\n\n*Status:* {wip} *Date:* 2020-01-02\n",
+        )
+        .unwrap();
+
+        //a miss parses the files and writes the cache
+        let adrs = list_all_adr_with_cache(&src, Some(&cache_path)).unwrap();
+        assert_eq!(2, adrs.len());
+        assert!(cache_path.exists());
+
+        //an unchanged file is taken from the cache, and is not parsed again
+        let mut cache = load_cache(&cache_path, &src);
+        let metadata = fs::metadata(&first).unwrap();
+        let mut tampered = cache.get(&src, &first, &metadata).unwrap();
+        tampered.title = String::from("Cached");
+        cache.insert(&tampered, &metadata);
+        store_cache(&cache_path, &cache).unwrap();
+        let adrs = list_all_adr_with_cache(&src, Some(&cache_path)).unwrap();
+        assert_eq!("Cached", adrs[0].title);
+        assert!(adrs[0].content.contains("= Use Kafka"));
+
+        //a changed file is parsed again, a removed file is removed from the cache
+        fs::write(
+            &first,
+            "= Use Kafka Streams\n\n*Status:* {wip} *Date:* 2020-01-03\n",
+        )
+        .unwrap();
+        fs::remove_file(src.join("0002-use-rust.adoc")).unwrap();
+        let adrs = list_all_adr_with_cache(&src, Some(&cache_path)).unwrap();
+        assert_eq!(1, adrs.len());
+        assert_eq!("Use Kafka Streams", adrs[0].title);
+        assert_eq!(1, load_cache(&cache_path, &src).entries.len());
+
+        //the cache of another directory is ignored
+        assert_eq!(0, load_cache(&cache_path, dir.path()).entries.len());
+    }
+}
@@ -0,0 +1,302 @@
+extern crate slog;
+use slog::*;
+
+use std::collections::HashMap;
+use std::path::Path;
+
+extern crate adr_config;
+
+use crate::adr_git::adr_id;
+use crate::adr_repo::*;
+
+fn get_logger() -> slog::Logger {
+    adr_config::logger::get_logger()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdrChange {
+    ADDED,
+    REMOVED,
+    /// The status has changed, from the given one
+    TRANSITIONED(Status),
+    /// The title has changed, from the given one
+    RETITLED(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ChangelogEntry {
+    /// The ADR as it is in the most recent set (or as it was, if it has been removed)
+    pub adr: Adr,
+    pub change: AdrChange,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ChangelogFormat {
+    ASCIIDOC,
+    MARKDOWN,
+}
+
+impl std::str::FromStr for ChangelogFormat {
+    type Err = String;
+
+    fn from_str(val: &str) -> std::result::Result<ChangelogFormat, String> {
+        match val {
+            "asciidoc" | "adoc" => Ok(ChangelogFormat::ASCIIDOC),
+            "markdown" | "md" => Ok(ChangelogFormat::MARKDOWN),
+            _ => Err(format!("Unknown changelog format [{}]", val)),
+        }
+    }
+}
+
+/// Returns the changes between two sets of ADRs (e.g. the ADRs at two git revisions, cf.
+/// `adr_git::list_all_adr_at_rev`). ADRs are matched by their ID, so that a renamed ADR is not seen as removed and
+/// added, or by their path if they have no ID or if their ID is not unique.
+///
+/// # Arguments
+///
+/// * `from` - the oldest set of ADRs
+/// * `to` - the most recent set of ADRs
+///
+pub fn get_changelog(from: Vec<Adr>, to: Vec<Adr>) -> Vec<ChangelogEntry> {
+    let unique_ids = |adrs: &[Adr]| {
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        for adr in adrs.iter().filter(|adr| adr.file_id != 0) {
+            *counts.entry(adr.file_id).or_insert(0) += 1;
+        }
+        counts
+            .into_iter()
+            .filter(|(_, count)| *count == 1)
+            .map(|(file_id, _)| file_id)
+            .collect::<Vec<usize>>()
+    };
+    let to_ids = unique_ids(&to);
+    let from_ids = unique_ids(&from);
+
+    let mut previous_by_id: HashMap<usize, Adr> = HashMap::new();
+    let mut previous: HashMap<String, Adr> = HashMap::new();
+    for adr in from {
+        match from_ids.contains(&adr.file_id) && to_ids.contains(&adr.file_id) {
+            true => previous_by_id.insert(adr.file_id, adr),
+            false => previous.insert(adr.file_path.clone(), adr),
+        };
+    }
+
+    let mut entries = Vec::new();
+    for adr in to {
+        let old = match previous_by_id.remove(&adr.file_id) {
+            Some(old) => Some(old),
+            None => previous.remove(&adr.file_path),
+        };
+        match old {
+            None => entries.push(ChangelogEntry {
+                adr,
+                change: AdrChange::ADDED,
+            }),
+            Some(old) => {
+                if old.status != adr.status {
+                    entries.push(ChangelogEntry {
+                        adr: adr.clone(),
+                        change: AdrChange::TRANSITIONED(old.status),
+                    });
+                }
+                if old.title != adr.title {
+                    entries.push(ChangelogEntry {
+                        adr,
+                        change: AdrChange::RETITLED(old.title),
+                    });
+                }
+            }
+        }
+    }
+    let mut removed: Vec<Adr> = previous
+        .into_values()
+        .chain(previous_by_id.into_values())
+        .collect();
+    removed.sort_by_key(|adr| adr.file_id);
+    for adr in removed {
+        entries.push(ChangelogEntry {
+            adr,
+            change: AdrChange::REMOVED,
+        });
+    }
+
+    debug!(get_logger(), "Got [{}] change(s)", entries.len());
+
+    entries
+}
+
+/// Renders the changes as a section of release notes, in AsciiDoc or Markdown
+///
+/// # Arguments
+///
+/// * `entries` - the changes, cf. `get_changelog`
+/// * `from` - the name of the oldest revision, e.g. `v3.1`
+/// * `to` - the name of the most recent revision, e.g. `v3.2`
+/// * `format` - the output format
+///
+pub fn render_changelog(
+    entries: &[ChangelogEntry],
+    from: &str,
+    to: &str,
+    format: ChangelogFormat,
+) -> String {
+    let (section, subsection, bullet) = match format {
+        ChangelogFormat::ASCIIDOC => ("==", "===", "*"),
+        ChangelogFormat::MARKDOWN => ("##", "###", "-"),
+    };
+    let name = |adr: &Adr| format!("{} - {}", adr_id(Path::new(&adr.file_path)), adr.title);
+
+    let mut added = Vec::new();
+    let mut transitioned = Vec::new();
+    let mut superseded = Vec::new();
+    let mut retitled = Vec::new();
+    let mut removed = Vec::new();
+    for entry in entries.iter() {
+        let adr = &entry.adr;
+        match &entry.change {
+            AdrChange::ADDED => added.push(format!("{} ({})", name(adr), adr.status.as_str())),
+            AdrChange::TRANSITIONED(_) if adr.status == Status::SUPERSEDED => {
+                let by = adr.references().join(" ");
+                superseded.push(match by.is_empty() {
+                    true => format!("{} is superseded", name(adr)),
+                    false => format!("{} is superseded by {}", name(adr), by),
+                })
+            }
+            AdrChange::TRANSITIONED(status) => transitioned.push(format!(
+                "{}: {} -> {}",
+                name(adr),
+                status.as_str(),
+                adr.status.as_str()
+            )),
+            AdrChange::RETITLED(title) => retitled.push(format!("{}: was {}", name(adr), title)),
+            AdrChange::REMOVED => removed.push(name(adr)),
+        }
+    }
+
+    let mut changelog = format!("{} Decisions changed from {} to {}\n", section, from, to);
+    if entries.is_empty() {
+        changelog.push_str("\nNo decision has changed.\n");
+    }
+    for (title, lines) in [
+        ("New decisions", added),
+        ("Status changes", transitioned),
+        ("Supersessions", superseded),
+        ("Retitled decisions", retitled),
+        ("Removed decisions", removed),
+    ]
+    .iter()
+    {
+        if lines.is_empty() {
+            continue;
+        }
+        changelog.push_str(&format!("\n{} {}\n\n", subsection, title));
+        for line in lines.iter() {
+            changelog.push_str(&format!("{} {}\n", bullet, line));
+        }
+    }
+
+    changelog
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adr_changelog::*;
+
+    fn adr(file_path: &str, title: &str, status: &str) -> Adr {
+        Adr::from(
+            String::from("/adr"),
+            String::from(file_path),
+            format!(
+                "= {}\n\n*Status:* {{{}}} *Date:* 2020-01-01\n",
+                title, status
+            ),
+        )
+    }
+
+    #[test]
+    fn test_get_changelog() {
+        let from = vec![
+            adr("0001-a.adoc", "Use Kafka", "decided"),
+            adr("0002-b.adoc", "Use REST", "wip"),
+            adr("0003-c.adoc", "Use XML", "decided"),
+            adr("0004-d.adoc", "Use FTP", "wip"),
+        ];
+        let mut to = vec![
+            adr("0001-a.adoc", "Use Kafka", "decided"),
+            adr("0002-b.adoc", "Use gRPC", "decided"),
+            adr("0003-c.adoc", "Use XML", "superseded"),
+            adr("0005-e.adoc", "Use JSON", "supersedes"),
+        ];
+        to[2].add_reference("0005-e.adoc");
+
+        let entries = get_changelog(from, to);
+        assert_eq!(5, entries.len());
+        assert_eq!(AdrChange::TRANSITIONED(Status::WIP), entries[0].change);
+        assert_eq!(
+            AdrChange::RETITLED(String::from("Use REST")),
+            entries[1].change
+        );
+        assert_eq!(AdrChange::TRANSITIONED(Status::DECIDED), entries[2].change);
+        assert_eq!(AdrChange::ADDED, entries[3].change);
+        assert_eq!(AdrChange::REMOVED, entries[4].change);
+        assert_eq!("0004-d.adoc", entries[4].adr.file_path);
+
+        let changelog = render_changelog(&entries, "v1", "v2", ChangelogFormat::ASCIIDOC);
+        assert_eq!(
+            "== Decisions changed from v1 to v2
+
+=== New decisions
+
+* 0005 - Use JSON (supersedes)
+
+=== Status changes
+
+* 0002 - Use gRPC: wip -> decided
+
+=== Supersessions
+
+* 0003 - Use XML is superseded by 0005-e.adoc
+
+=== Retitled decisions
+
+* 0002 - Use gRPC: was Use REST
+
+=== Removed decisions
+
+* 0004 - Use FTP
+",
+            changelog
+        );
+
+        let changelog = render_changelog(&[], "v1", "v2", ChangelogFormat::MARKDOWN);
+        assert_eq!(
+            "## Decisions changed from v1 to v2\n\nNo decision has changed.\n",
+            changelog
+        );
+    }
+
+    #[test]
+    fn test_get_changelog_renamed() {
+        let from = vec![
+            adr("0002-use-rest.adoc", "Use REST", "wip"),
+            adr("0004-a.adoc", "Use FTP", "wip"),
+        ];
+        let to = vec![
+            adr("0002-use-grpc.adoc", "Use gRPC", "wip"),
+            adr("0004-a.adoc", "Use FTP", "wip"),
+            adr("0004-b.adoc", "Use SFTP", "wip"),
+        ];
+
+        //matched by ID
+        let entries = get_changelog(from, to);
+        assert_eq!(2, entries.len());
+        assert_eq!(
+            AdrChange::RETITLED(String::from("Use REST")),
+            entries[0].change
+        );
+        assert_eq!("0002-use-grpc.adoc", entries[0].adr.file_path);
+        //matched by path as the ID is not unique
+        assert_eq!(AdrChange::ADDED, entries[1].change);
+        assert_eq!("0004-b.adoc", entries[1].adr.file_path);
+    }
+}
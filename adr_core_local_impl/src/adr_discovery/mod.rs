@@ -0,0 +1,223 @@
+extern crate slog;
+use slog::*;
+
+use std::path::{Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+extern crate adr_config;
+use adr_config::config::AdrToolConfig;
+
+fn get_logger() -> slog::Logger {
+    adr_config::logger::get_logger()
+}
+
+/// The file of the directory of the ADRs listing the gitignore-style patterns of the files which are not ADRs
+pub const ADR_IGNORE_FILE: &str = ".adrignore";
+
+/// The rules telling which files of the directory of the ADRs are ADRs: the hidden entries, the templates, the
+/// patterns of `.adrignore` and `AdrToolConfig.adr_exclude` are skipped, and the files must have one of the
+/// `AdrToolConfig.adr_extensions` and match one of the `AdrToolConfig.adr_include` (if any).
+#[derive(Debug)]
+pub struct AdrDiscovery {
+    dir: PathBuf,
+    extensions: Vec<String>,
+    exclude: Gitignore,
+    include: Option<Gitignore>,
+}
+
+impl AdrDiscovery {
+    /// Builds the rules of the directory `dir`. The invalid patterns are logged and ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `cfg` - The whole config object
+    /// * `dir` - the directory of the ADRs, the patterns are relative to
+    ///
+    pub fn new(cfg: &AdrToolConfig, dir: &Path) -> AdrDiscovery {
+        let mut exclude = GitignoreBuilder::new(dir);
+        let adr_ignore = dir.join(ADR_IGNORE_FILE);
+        if adr_ignore.is_file() {
+            if let Some(why) = exclude.add(&adr_ignore) {
+                warn!(
+                    get_logger(),
+                    "Unable to read [{}] - [{}]",
+                    adr_ignore.display(),
+                    why
+                );
+            }
+        }
+        for pattern in cfg.adr_exclude.iter() {
+            add_pattern(&mut exclude, pattern);
+        }
+        for pattern in get_template_patterns(cfg, dir).iter() {
+            add_pattern(&mut exclude, pattern);
+        }
+
+        let include = match cfg.adr_include.is_empty() {
+            true => None,
+            false => {
+                let mut include = GitignoreBuilder::new(dir);
+                for pattern in cfg.adr_include.iter() {
+                    add_pattern(&mut include, pattern);
+                }
+                Some(build(include))
+            }
+        };
+
+        AdrDiscovery {
+            dir: dir.to_path_buf(),
+            extensions: cfg.adr_extensions.clone(),
+            exclude: build(exclude),
+            include,
+        }
+    }
+
+    /// Returns true if the entry is an ADR or, for a directory, if it may contain ADRs
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - the path of the entry, in the directory of the ADRs
+    /// * `is_dir` - whether the entry is a directory
+    ///
+    pub fn is_adr(&self, path: &Path, is_dir: bool) -> bool {
+        let is_hidden = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.starts_with('.'))
+            .unwrap_or(false);
+        if is_hidden || self.matches(&self.exclude, path, is_dir) {
+            return false;
+        }
+        if is_dir {
+            return true;
+        }
+
+        let has_extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| self.extensions.iter().any(|val| val == extension))
+            .unwrap_or(false);
+
+        has_extension
+            && match &self.include {
+                Some(include) => self.matches(include, path, false),
+                None => true,
+            }
+    }
+
+    /// Returns true if the path or one of its parents matches the patterns (i.e. would be ignored by git)
+    fn matches(&self, patterns: &Gitignore, path: &Path, is_dir: bool) -> bool {
+        match path.strip_prefix(&self.dir) {
+            Ok(relative) if relative != Path::new("") => patterns
+                .matched_path_or_any_parents(relative, is_dir)
+                .is_ignore(),
+            _ => false,
+        }
+    }
+}
+
+/// The patterns of the templates of the configuration and of the workspaces, when they are in `dir`: the template
+/// directory, or the template file if the templates are in `dir` itself
+fn get_template_patterns(cfg: &AdrToolConfig, dir: &Path) -> Vec<String> {
+    let template_dirs = std::iter::once(&cfg.adr_template_dir).chain(
+        cfg.workspaces
+            .iter()
+            .map(|workspace| &workspace.adr_template_dir),
+    );
+
+    let mut patterns = Vec::new();
+    for template_dir in template_dirs {
+        let template_dir = Path::new(template_dir);
+        let (relative, is_dir) = match template_dir.strip_prefix(dir) {
+            Ok(relative) if relative == Path::new("") => {
+                (PathBuf::from(&cfg.adr_template_file), false)
+            }
+            Ok(relative) => (relative.to_path_buf(), true),
+            Err(_why) => continue,
+        };
+        let relative: Vec<String> = relative
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy().to_string())
+            .collect();
+        patterns.push(format!(
+            "/{}{}",
+            relative.join("/"),
+            match is_dir {
+                true => "/",
+                false => "",
+            }
+        ));
+    }
+
+    patterns
+}
+
+fn add_pattern(builder: &mut GitignoreBuilder, pattern: &str) {
+    if let Err(why) = builder.add_line(None, pattern) {
+        warn!(get_logger(), "Invalid pattern [{}] - [{}]", pattern, why);
+    }
+}
+
+fn build(builder: GitignoreBuilder) -> Gitignore {
+    match builder.build() {
+        Ok(gitignore) => gitignore,
+        Err(why) => {
+            warn!(get_logger(), "Invalid patterns - [{}]", why);
+            Gitignore::empty()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adr_discovery::*;
+    use std::fs;
+    use tempdir::TempDir;
+
+    #[test]
+    fn test_is_adr() {
+        let dir = match TempDir::new("adr_discovery") {
+            Ok(dir) => dir,
+            Err(why) => {
+                println!("{:?}", why);
+                panic!("{}", why);
+            }
+        };
+        let src = dir.path();
+        fs::write(src.join(ADR_IGNORE_FILE), "# drafts\n_drafts/\n*.html\n").unwrap();
+
+        let mut cfg = AdrToolConfig {
+            adr_template_dir: format!("{}", src.join("templates").display()),
+            adr_exclude: vec![String::from("includes/")],
+            adr_extensions: vec![String::from("adoc"), String::from("md")],
+            ..AdrToolConfig::default()
+        };
+        let discovery = AdrDiscovery::new(&cfg, src);
+
+        assert!(discovery.is_adr(&src.join("infra"), true));
+        assert!(discovery.is_adr(&src.join("infra/0001-use-kafka.adoc"), false));
+        assert!(discovery.is_adr(&src.join("0002-use-rust.md"), false));
+        assert!(!discovery.is_adr(&src.join("0003-use-rust.txt"), false));
+        assert!(!discovery.is_adr(&src.join(".0004-hidden.adoc"), false));
+        //.adrignore
+        assert!(!discovery.is_adr(&src.join("_drafts"), true));
+        assert!(!discovery.is_adr(&src.join("_drafts/0005-draft.adoc"), false));
+        assert!(!discovery.is_adr(&src.join("0001-use-kafka.html"), false));
+        //adr_exclude and templates
+        assert!(!discovery.is_adr(&src.join("includes"), true));
+        assert!(!discovery.is_adr(&src.join("templates"), true));
+        assert!(!discovery.is_adr(&src.join("templates/adr-template-v0.1.adoc"), false));
+
+        //adr_include
+        cfg.adr_include = vec![String::from("infra/**")];
+        let discovery = AdrDiscovery::new(&cfg, src);
+        assert!(discovery.is_adr(&src.join("infra/0001-use-kafka.adoc"), false));
+        assert!(!discovery.is_adr(&src.join("0002-use-rust.md"), false));
+
+        //templates in the directory of the ADRs
+        cfg.adr_template_dir = format!("{}", src.display());
+        let discovery = AdrDiscovery::new(&cfg, src);
+        assert!(!discovery.is_adr(&src.join("adr-template-v0.1.adoc"), false));
+    }
+}
@@ -0,0 +1,129 @@
+extern crate slog;
+use slog::*;
+
+extern crate adr_config;
+
+use chrono::prelude::*;
+
+use crate::adr_filter::parse_date;
+use crate::adr_repo::*;
+
+fn get_logger() -> slog::Logger {
+    adr_config::logger::get_logger()
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum DueReason {
+    /// The ADR has been wip for the given number of days
+    STALE(i64),
+    /// The ADR is still in force and its revisit date (`:revisit:` or `:review-by:`) is over
+    REVISIT(String),
+}
+
+impl DueReason {
+    pub fn as_string(&self) -> String {
+        match self {
+            DueReason::STALE(days) => format!("wip for {} days", days),
+            DueReason::REVISIT(date) => format!("revisit date {} is over", date),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DueAdr {
+    pub adr: Adr,
+    pub reason: DueReason,
+}
+
+/// Returns the ADRs which need some attention at `today`, i.e. the ADRs which have been wip for more than
+/// `wip_max_age_days` days (0 to disable) and the decided ones whose revisit date is over.
+///
+/// # Arguments
+///
+/// * `adrs` - the ADRs to check
+/// * `wip_max_age_days` - cf. `AdrToolConfig.wip_max_age_days`
+/// * `today` - the reference date
+///
+pub fn get_due_adr(adrs: Vec<Adr>, wip_max_age_days: u32, today: NaiveDate) -> Vec<DueAdr> {
+    let mut due = Vec::new();
+    for adr in adrs {
+        let reason = match adr.status {
+            Status::WIP if wip_max_age_days > 0 => match parse_date(&adr.date) {
+                Some(date) if (today - date).num_days() > wip_max_age_days as i64 => {
+                    Some(DueReason::STALE((today - date).num_days()))
+                }
+                _ => None,
+            },
+            Status::DECIDED | Status::COMPLETES | Status::SUPERSEDES => {
+                match adr.revisit.as_ref().and_then(|date| parse_date(date)) {
+                    Some(date) if date <= today => {
+                        Some(DueReason::REVISIT(date.format("%Y-%m-%d").to_string()))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        if let Some(reason) = reason {
+            debug!(get_logger(), "ADR [{}] is due - [{:?}]", adr.path(), reason);
+            due.push(DueAdr { adr, reason });
+        }
+    }
+
+    due
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adr_due::*;
+
+    fn adr(file_path: &str, status: &str, date: &str, revisit: &str) -> Adr {
+        Adr::from(
+            String::from("/adr"),
+            String::from(file_path),
+            format!(
+                "{}\n= title\n\n*Status:* {{{}}} *Date:* {}\n",
+                revisit, status, date
+            ),
+        )
+    }
+
+    #[test]
+    fn test_get_due_adr() {
+        let adrs = vec![
+            adr("01-a.adoc", "wip", "2020-01-01", ""),
+            adr("02-b.adoc", "wip", "2020-03-01", ""),
+            adr("03-c.adoc", "decided", "2019-01-01", ":revisit: 2020-03-01"),
+            adr(
+                "04-d.adoc",
+                "decided",
+                "2019-01-01",
+                ":review-by: 2020-06-01",
+            ),
+            adr(
+                "05-e.adoc",
+                "superseded",
+                "2019-01-01",
+                ":revisit: 2020-01-01",
+            ),
+            adr("06-f.adoc", "decided", "2019-01-01", ""),
+        ];
+
+        let today = NaiveDate::from_ymd_opt(2020, 4, 1).unwrap();
+        let due = get_due_adr(adrs.clone(), 60, today);
+        assert_eq!(2, due.len());
+        assert_eq!("01-a.adoc", due[0].adr.file_path);
+        assert_eq!(DueReason::STALE(91), due[0].reason);
+        assert_eq!("wip for 91 days", due[0].reason.as_string());
+        assert_eq!("03-c.adoc", due[1].adr.file_path);
+        assert_eq!(
+            DueReason::REVISIT(String::from("2020-03-01")),
+            due[1].reason
+        );
+
+        let due = get_due_adr(adrs, 0, today);
+        assert_eq!(1, due.len());
+        assert_eq!("03-c.adoc", due[0].adr.file_path);
+    }
+}
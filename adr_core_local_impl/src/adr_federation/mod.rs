@@ -0,0 +1,186 @@
+extern crate slog;
+use slog::*;
+
+use regex::Regex;
+use std::io::{self};
+use std::path::Path;
+
+extern crate adr_config;
+use adr_config::config::WorkspaceDefinition;
+
+use crate::adr_repo::*;
+
+fn get_logger() -> slog::Logger {
+    adr_config::logger::get_logger()
+}
+
+/// Lists the ADRs of all the workspaces, each one qualified by its workspace (cf. `Adr::qualified_id`). The ADRs are
+/// listed without their content, cf. `Adr::load_content`.
+pub fn list_all_federated_adr(workspaces: &[WorkspaceDefinition]) -> io::Result<Vec<Adr>> {
+    if workspaces.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "No workspace - cf. adr workspace add",
+        ));
+    }
+
+    let mut results = Vec::new();
+    for workspace in workspaces.iter() {
+        let mut adrs = scan_adr(Path::new(&workspace.adr_src_dir))?;
+        debug!(
+            get_logger(),
+            "Got [{}] ADR(s) in workspace [{}]",
+            adrs.len(),
+            workspace.name
+        );
+        for adr in adrs.iter_mut() {
+            adr.workspace = workspace.name.clone();
+        }
+        results.append(&mut adrs);
+    }
+
+    Ok(results)
+}
+
+/// Parses a reference to an ADR of a workspace, e.g. `platform:0012`, into the name of the workspace and the ID
+///
+/// # Example
+///
+/// ```
+/// use adr_core::adr_federation::parse_qualified_id;
+///
+/// assert_eq!(Some((String::from("platform"), 12)), parse_qualified_id("platform:0012"));
+/// assert_eq!(None, parse_qualified_id("0012-my-decision.adoc"));
+/// ```
+pub fn parse_qualified_id(reference: &str) -> Option<(String, usize)> {
+    lazy_static! {
+        static ref RE_QUALIFIED_ID: Regex = Regex::new(r"^([\w-]+):([0-9]+)$").unwrap();
+    }
+
+    match RE_QUALIFIED_ID.captures(reference.trim()) {
+        Some(cap) => match cap[2].parse() {
+            Ok(id) => Some((cap[1].to_string(), id)),
+            Err(_why) => None,
+        },
+        None => None,
+    }
+}
+
+/// Resolves a reference written in the ADR `from` (cf. `Adr::references`) against the ADRs of all the workspaces:
+/// either a qualified ID (e.g. `platform:0012`) or the file name of an ADR of the same workspace.
+pub fn resolve_reference<'a>(adrs: &'a [Adr], from: &Adr, reference: &str) -> Option<&'a Adr> {
+    match parse_qualified_id(reference) {
+        Some((workspace, id)) => adrs
+            .iter()
+            .find(|adr| adr.workspace == workspace && adr.file_id == id),
+        None => adrs
+            .iter()
+            .find(|adr| adr.workspace == from.workspace && adr.file_name == reference),
+    }
+}
+
+/// Returns the ADR of the workspace given by its qualified ID (e.g. `platform:0012`), with its content, or an error if
+/// there is none
+pub fn find_adr(workspaces: &[WorkspaceDefinition], qualified_id: &str) -> io::Result<Adr> {
+    let (workspace, id) = match parse_qualified_id(qualified_id) {
+        Some(val) => val,
+        None => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Invalid reference [{}] - expected <workspace>:<id>",
+                    qualified_id
+                ),
+            ))
+        }
+    };
+    let workspace = match workspaces.iter().find(|def| def.name == workspace) {
+        Some(workspace) => workspace,
+        None => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Unknown workspace [{}] - cf. adr workspace list", workspace),
+            ))
+        }
+    };
+
+    let adrs = list_all_federated_adr(std::slice::from_ref(workspace))?;
+    match adrs.into_iter().find(|adr| adr.file_id == id) {
+        Some(mut adr) => {
+            adr.load_content()?;
+            Ok(adr)
+        }
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Unable to find [{}]", qualified_id),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adr_federation::*;
+    use std::fs;
+    use tempdir::TempDir;
+
+    fn workspace(name: &str, dir: &Path) -> WorkspaceDefinition {
+        WorkspaceDefinition {
+            name: String::from(name),
+            adr_src_dir: format!("{}", dir.display()),
+            adr_template_dir: String::new(),
+            adr_search_index: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_list_all_federated_adr() {
+        let dir = match TempDir::new("adr_federation") {
+            Ok(dir) => dir,
+            Err(why) => {
+                println!("{:?}", why);
+                panic!("{}", why);
+            }
+        };
+        let platform = dir.path().join("platform");
+        let product = dir.path().join("product");
+        fs::create_dir_all(&platform).unwrap();
+        fs::create_dir_all(&product).unwrap();
+        fs::write(
+            platform.join("0012-use-kafka.adoc"),
+            "= Use Kafka\n\n*Status:* {completed} product:0003 *Date:* 2020-01-01\n",
+        )
+        .unwrap();
+        fs::write(
+            product.join("0003-use-kafka-streams.adoc"),
+            "= Use Kafka Streams\n\n*Status:* {completes} platform:0012 0002-missing.adoc *Date:* 2020-01-02\n",
+        )
+        .unwrap();
+
+        let workspaces = vec![
+            workspace("platform", &platform),
+            workspace("product", &product),
+        ];
+        let mut adrs = list_all_federated_adr(&workspaces).unwrap();
+        assert_eq!(2, adrs.len());
+        assert!(adrs[1].content.is_empty());
+        //read from the file
+        let references = adrs[1].references();
+        assert!(adrs[1].load_content().unwrap().contains("platform:0012"));
+        assert_eq!(references, adrs[1].references());
+        assert_eq!("platform:0012", adrs[0].qualified_id());
+        assert_eq!("product:0003", adrs[1].qualified_id());
+
+        assert_eq!(
+            Some("Use Kafka"),
+            resolve_reference(&adrs, &adrs[1], &references[0]).map(|adr| adr.title.as_str())
+        );
+        assert!(resolve_reference(&adrs, &adrs[1], &references[1]).is_none());
+
+        let adr = find_adr(&workspaces, "product:3").unwrap();
+        assert_eq!("Use Kafka Streams", adr.title);
+        assert!(adr.content.contains("platform:0012"));
+        assert!(find_adr(&workspaces, "product:4").is_err());
+        assert!(find_adr(&workspaces, "checkout:3").is_err());
+        assert!(list_all_federated_adr(&[]).is_err());
+    }
+}
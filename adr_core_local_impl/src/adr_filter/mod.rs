@@ -0,0 +1,636 @@
+extern crate slog;
+use slog::*;
+
+use std::cmp::Ordering;
+use std::path::Path;
+
+extern crate adr_config;
+
+use chrono::prelude::*;
+
+use crate::adr_repo::*;
+
+fn get_logger() -> slog::Logger {
+    adr_config::logger::get_logger()
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum SortKey {
+    ID,
+    DATE,
+    TITLE,
+    STATUS,
+}
+
+//`#[default]` on an enum variant needs Rust 1.62
+#[allow(clippy::derivable_impls)]
+impl Default for SortKey {
+    fn default() -> Self {
+        SortKey::ID
+    }
+}
+
+impl std::str::FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(val: &str) -> std::result::Result<SortKey, String> {
+        match val {
+            "id" => Ok(SortKey::ID),
+            "date" => Ok(SortKey::DATE),
+            "title" => Ok(SortKey::TITLE),
+            "status" => Ok(SortKey::STATUS),
+            _ => Err(format!(
+                "Unknown sort key [{}] - expected id, date, title or status",
+                val
+            )),
+        }
+    }
+}
+
+/// The criteria used to select (and order) ADRs. All the criteria have to match.
+///
+/// # Example
+///
+/// ```
+/// use adr_core::adr_filter::*;
+///
+/// let filter = AdrFilter {
+///     tag: Some(String::from("security")),
+///     expression: Some(parse_expression("status=decided or status=wip").unwrap()),
+///     sort: SortKey::DATE,
+///     reverse: true,
+///     limit: Some(10),
+///     ..AdrFilter::default()
+/// };
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct AdrFilter {
+    pub status: Option<Status>,
+    /// Matches the tag and all its descendants (e.g. `security` matches `security/authn`)
+    pub tag: Option<String>,
+    /// Inclusive, `YYYY-MM-DD`
+    pub from_date: Option<NaiveDate>,
+    /// Inclusive, `YYYY-MM-DD`
+    pub to_date: Option<NaiveDate>,
+    /// The sub-directory (relative to `adr_src_dir`) in which the ADRs are
+    pub path: Option<String>,
+    /// The whole name of one of the deciders, case-insensitive and with or without `@`
+    pub decider: Option<String>,
+    pub expression: Option<Expression>,
+    pub sort: SortKey,
+    pub reverse: bool,
+    pub limit: Option<usize>,
+}
+
+impl AdrFilter {
+    pub fn matches(&self, adr: &Adr) -> bool {
+        let date = parse_date(&adr.date);
+
+        let matches_status = match self.status {
+            Some(status) => adr.status == status,
+            None => true,
+        };
+        let matches_tag = match &self.tag {
+            Some(tag) => adr.matches_tag(tag),
+            None => true,
+        };
+        let matches_from_date = match (self.from_date, date) {
+            (Some(from), Some(date)) => date >= from,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        let matches_to_date = match (self.to_date, date) {
+            (Some(to), Some(date)) => date <= to,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        let matches_path = match &self.path {
+            Some(path) => is_in_path(adr, path),
+            None => true,
+        };
+        let matches_decider = match &self.decider {
+            Some(decider) => is_decided_by(adr, decider),
+            None => true,
+        };
+        let matches_expression = match &self.expression {
+            Some(expression) => expression.matches(adr),
+            None => true,
+        };
+
+        matches_status
+            && matches_tag
+            && matches_from_date
+            && matches_to_date
+            && matches_path
+            && matches_decider
+            && matches_expression
+    }
+}
+
+/// Keeps the ADRs matching `filter`, sorted and limited as specified by `filter`.
+pub fn filter_adr(adrs: Vec<Adr>, filter: &AdrFilter) -> Vec<Adr> {
+    let mut results: Vec<Adr> = adrs.into_iter().filter(|adr| filter.matches(adr)).collect();
+
+    //stable sort, so ADRs with the same key stay sorted by id
+    results.sort_by_key(|adr| adr.file_id);
+    match filter.sort {
+        SortKey::ID => (),
+        SortKey::DATE => results.sort_by(|a, b| compare_dates(&a.date, &b.date)),
+        SortKey::TITLE => results.sort_by_key(|adr| adr.title.to_lowercase()),
+        SortKey::STATUS => results.sort_by_key(|adr| adr.status.lifecycle_order()),
+    };
+    if filter.reverse {
+        results.reverse();
+    }
+    if let Some(limit) = filter.limit {
+        results.truncate(limit);
+    }
+
+    debug!(
+        get_logger(),
+        "[{}] ADRs match [{:?}]",
+        results.len(),
+        filter
+    );
+
+    results
+}
+
+pub fn parse_date(val: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(val.trim(), "%Y-%m-%d").ok()
+}
+
+fn compare_dates(a: &str, b: &str) -> Ordering {
+    //ADRs without date come first
+    parse_date(a).cmp(&parse_date(b))
+}
+
+fn is_in_path(adr: &Adr, path: &str) -> bool {
+    let path = path.trim_start_matches("./");
+    Path::new(&adr.file_path).starts_with(path)
+}
+
+fn normalize_decider(decider: &str) -> String {
+    decider.trim().trim_start_matches('@').to_lowercase()
+}
+
+fn is_decided_by(adr: &Adr, decider: &str) -> bool {
+    let decider = normalize_decider(decider);
+    adr.deciders.iter().any(|d| normalize_decider(d) == decider)
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Operator {
+    EQ,
+    NE,
+    LT,
+    LE,
+    GT,
+    GE,
+    CONTAINS,
+}
+
+/// A boolean expression over the ADR fields, cf. `parse_expression`
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expression {
+    AND(Box<Expression>, Box<Expression>),
+    OR(Box<Expression>, Box<Expression>),
+    NOT(Box<Expression>),
+    COMPARE(String, Operator, String),
+}
+
+impl Expression {
+    pub fn matches(&self, adr: &Adr) -> bool {
+        match self {
+            Expression::AND(left, right) => left.matches(adr) && right.matches(adr),
+            Expression::OR(left, right) => left.matches(adr) || right.matches(adr),
+            Expression::NOT(expression) => !expression.matches(adr),
+            Expression::COMPARE(field, operator, value) => compare(adr, field, *operator, value),
+        }
+    }
+}
+
+fn compare(adr: &Adr, field: &str, operator: Operator, value: &str) -> bool {
+    //fields which can only be (not) equal
+    let matches = match field {
+        "status" => {
+            Some(adr.status == Status::from_str(value.to_string()) || adr.status.as_str() == value)
+        }
+        "tag" => Some(adr.matches_tag(value)),
+        "path" => Some(is_in_path(adr, value)),
+        "decider" => Some(is_decided_by(adr, value)),
+        "title" if operator == Operator::CONTAINS => Some(
+            adr.title
+                .to_lowercase()
+                .contains(value.to_lowercase().as_str()),
+        ),
+        _ => None,
+    };
+    if let Some(matches) = matches {
+        return match operator {
+            Operator::NE => !matches,
+            _ => matches,
+        };
+    }
+
+    let ordering = match field {
+        "title" => Some(adr.title.to_lowercase().cmp(&value.to_lowercase())),
+        "id" => value.parse::<usize>().ok().map(|id| adr.file_id.cmp(&id)),
+        "date" => match (parse_date(&adr.date), parse_date(value)) {
+            (Some(date), Some(value)) => Some(date.cmp(&value)),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match ordering {
+        Some(ordering) => match operator {
+            Operator::EQ | Operator::CONTAINS => ordering == Ordering::Equal,
+            Operator::NE => ordering != Ordering::Equal,
+            Operator::LT => ordering == Ordering::Less,
+            Operator::LE => ordering != Ordering::Greater,
+            Operator::GT => ordering == Ordering::Greater,
+            Operator::GE => ordering != Ordering::Less,
+        },
+        None => false,
+    }
+}
+
+const FIELDS: &[&str] = &["status", "tag", "date", "path", "decider", "title", "id"];
+
+/// The fields which can only be (not) equal, cf. `compare`
+const EQUALITY_FIELDS: &[&str] = &["status", "tag", "path", "decider"];
+
+/// Checks that the operator applies to the field, that the value of a status is known, and that the value of a date
+/// or an ID can be parsed
+fn check_comparison(
+    field: &str,
+    operator: Operator,
+    value: &str,
+) -> std::result::Result<(), String> {
+    if operator == Operator::CONTAINS && field != "title" {
+        return Err(format!(
+            "Operator [~] does not apply to [{}] - only to [title]",
+            field
+        ));
+    }
+    if EQUALITY_FIELDS.contains(&field) && operator != Operator::EQ && operator != Operator::NE {
+        return Err(format!("[{}] can only be compared with [=] or [!=]", field));
+    }
+    if field == "status"
+        && Status::from_str(value.to_string()) == Status::NONE
+        && value != Status::NONE.as_str()
+    {
+        return Err(format!("Unknown status [{}]", value));
+    }
+    if field == "date" && parse_date(value).is_none() {
+        return Err(format!("Invalid date [{}] - expected YYYY-MM-DD", value));
+    }
+    if field == "id" && value.parse::<usize>().is_err() {
+        return Err(format!("Invalid ID [{}] - expected a number", value));
+    }
+
+    Ok(())
+}
+
+/// Parses a boolean expression such as `status=decided and (tag=security or date>=2020-01-01)`.
+///
+/// * fields are `status`, `tag`, `date`, `path`, `decider`, `title` and `id`
+/// * operators are `=`, `!=`, `<`, `<=`, `>`, `>=` and `~` (contains, for `title`), `status`, `tag`, `path` and
+///   `decider` can only be (not) equal
+/// * values with spaces have to be quoted, e.g. `decider="John Smith"`
+/// * expressions can be combined with `and`, `or`, `not` and parentheses
+///
+/// # Example
+///
+/// ```
+/// use adr_core::adr_filter::parse_expression;
+///
+/// assert!(parse_expression("status=decided and not tag=security").is_ok());
+/// assert!(parse_expression("status=decided and").is_err());
+/// ```
+pub fn parse_expression(val: &str) -> std::result::Result<Expression, String> {
+    let tokens = tokenize(val)?;
+    let mut parser = Parser {
+        tokens,
+        position: 0,
+    };
+    let expression = parser.parse_or()?;
+    match parser.peek() {
+        None => Ok(expression),
+        Some(token) => Err(format!("Unexpected [{:?}] in [{}]", token, val)),
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Word(String),
+    Operator(Operator),
+    Open,
+    Close,
+}
+
+fn tokenize(val: &str) -> std::result::Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = val.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' => (),
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '=' => tokens.push(Token::Operator(Operator::EQ)),
+            '~' => tokens.push(Token::Operator(Operator::CONTAINS)),
+            '!' | '<' | '>' => {
+                let has_equal = chars.peek() == Some(&'=');
+                if has_equal {
+                    chars.next();
+                }
+                tokens.push(Token::Operator(match (c, has_equal) {
+                    ('!', true) => Operator::NE,
+                    ('<', false) => Operator::LT,
+                    ('<', true) => Operator::LE,
+                    ('>', false) => Operator::GT,
+                    ('>', true) => Operator::GE,
+                    _ => return Err(String::from("Expected [!=]")),
+                }));
+            }
+            '"' => {
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(format!("Missing closing quote in [{}]", val)),
+                    }
+                }
+                tokens.push(Token::Word(word));
+            }
+            _ => {
+                let mut word = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if " \t()=~!<>\"".contains(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(word)) => word.eq_ignore_ascii_case(keyword),
+            _ => false,
+        }
+    }
+
+    fn parse_or(&mut self) -> std::result::Result<Expression, String> {
+        let mut expression = self.parse_and()?;
+        while self.is_keyword("or") {
+            self.next();
+            expression = Expression::OR(Box::new(expression), Box::new(self.parse_and()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_and(&mut self) -> std::result::Result<Expression, String> {
+        let mut expression = self.parse_not()?;
+        while self.is_keyword("and") {
+            self.next();
+            expression = Expression::AND(Box::new(expression), Box::new(self.parse_not()?));
+        }
+        Ok(expression)
+    }
+
+    fn parse_not(&mut self) -> std::result::Result<Expression, String> {
+        if self.is_keyword("not") {
+            self.next();
+            return Ok(Expression::NOT(Box::new(self.parse_not()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> std::result::Result<Expression, String> {
+        match self.next() {
+            Some(Token::Open) => {
+                let expression = self.parse_or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expression),
+                    _ => Err(String::from("Missing closing parenthesis")),
+                }
+            }
+            Some(Token::Word(field)) => {
+                let field = field.to_lowercase();
+                if !FIELDS.contains(&field.as_str()) {
+                    return Err(format!(
+                        "Unknown field [{}] - expected one of {:?}",
+                        field, FIELDS
+                    ));
+                }
+                let operator = match self.next() {
+                    Some(Token::Operator(operator)) => operator,
+                    _ => return Err(format!("Expected an operator after [{}]", field)),
+                };
+                match self.next() {
+                    Some(Token::Word(value)) => {
+                        check_comparison(&field, operator, &value)?;
+                        Ok(Expression::COMPARE(field, operator, value))
+                    }
+                    _ => Err(format!("Expected a value after [{}]", field)),
+                }
+            }
+            Some(token) => Err(format!("Unexpected [{:?}]", token)),
+            None => Err(String::from("Unexpected end of expression")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adr_filter::*;
+
+    fn adr(file_path: &str, title: &str, status: &str, date: &str, tags: &str) -> Adr {
+        Adr::from(
+            String::from("/adr"),
+            String::from(file_path),
+            format!(
+                "= {}\n\n*Status:* {{{}}} *Date:* {}\n\n|_Decision Makers_ |@John Smith\n\n{}",
+                title, status, date, tags
+            ),
+        )
+    }
+
+    fn adrs() -> Vec<Adr> {
+        vec![
+            adr(
+                "01-first.adoc",
+                "First",
+                "decided",
+                "2020-01-10",
+                "[tags]#security/authn#",
+            ),
+            adr(
+                "infra/02-second.adoc",
+                "Second",
+                "wip",
+                "2020-03-01",
+                "[tags]#infra#",
+            ),
+            adr(
+                "infra/network/03-third.adoc",
+                "Third",
+                "decided",
+                "2019-12-24",
+                "[tags]#security#",
+            ),
+        ]
+    }
+
+    fn ids(adrs: Vec<Adr>) -> Vec<usize> {
+        adrs.iter().map(|adr| adr.file_id).collect()
+    }
+
+    #[test]
+    fn test_filter_adr() {
+        let filter = AdrFilter {
+            status: Some(Status::DECIDED),
+            ..AdrFilter::default()
+        };
+        assert_eq!(vec![1, 3], ids(filter_adr(adrs(), &filter)));
+
+        let filter = AdrFilter {
+            tag: Some(String::from("security")),
+            from_date: parse_date("2020-01-01"),
+            ..AdrFilter::default()
+        };
+        assert_eq!(vec![1], ids(filter_adr(adrs(), &filter)));
+
+        let filter = AdrFilter {
+            path: Some(String::from("./infra")),
+            decider: Some(String::from("john smith")),
+            ..AdrFilter::default()
+        };
+        assert_eq!(vec![2, 3], ids(filter_adr(adrs(), &filter)));
+
+        let filter = AdrFilter {
+            decider: Some(String::from("@jane")),
+            ..AdrFilter::default()
+        };
+        assert_eq!(0, filter_adr(adrs(), &filter).len());
+
+        //whole names only
+        let filter = AdrFilter {
+            decider: Some(String::from("john")),
+            ..AdrFilter::default()
+        };
+        assert_eq!(0, filter_adr(adrs(), &filter).len());
+    }
+
+    #[test]
+    fn test_sort_adr() {
+        let filter = AdrFilter {
+            sort: SortKey::DATE,
+            ..AdrFilter::default()
+        };
+        assert_eq!(vec![3, 1, 2], ids(filter_adr(adrs(), &filter)));
+
+        let filter = AdrFilter {
+            sort: SortKey::STATUS,
+            reverse: true,
+            limit: Some(2),
+            ..AdrFilter::default()
+        };
+        assert_eq!(vec![3, 1], ids(filter_adr(adrs(), &filter)));
+
+        let filter = AdrFilter {
+            sort: SortKey::TITLE,
+            ..AdrFilter::default()
+        };
+        assert_eq!(vec![1, 2, 3], ids(filter_adr(adrs(), &filter)));
+    }
+
+    #[test]
+    fn test_filter_adr_with_expression() {
+        let filter = AdrFilter {
+            expression: Some(parse_expression("status=decided and tag=security").unwrap()),
+            ..AdrFilter::default()
+        };
+        assert_eq!(vec![1, 3], ids(filter_adr(adrs(), &filter)));
+
+        let filter = AdrFilter {
+            expression: Some(
+                parse_expression("not (tag=security/authn or date < 2020-01-01) and id>=2")
+                    .unwrap(),
+            ),
+            ..AdrFilter::default()
+        };
+        assert_eq!(vec![2], ids(filter_adr(adrs(), &filter)));
+
+        let filter = AdrFilter {
+            expression: Some(parse_expression("title~ir or decider=\"John Smith\"").unwrap()),
+            ..AdrFilter::default()
+        };
+        assert_eq!(vec![1, 2, 3], ids(filter_adr(adrs(), &filter)));
+    }
+
+    #[test]
+    fn test_parse_expression() {
+        assert_eq!(
+            Ok(Expression::AND(
+                Box::new(Expression::COMPARE(
+                    String::from("status"),
+                    Operator::EQ,
+                    String::from("decided")
+                )),
+                Box::new(Expression::COMPARE(
+                    String::from("tag"),
+                    Operator::NE,
+                    String::from("security")
+                )),
+            )),
+            parse_expression("status=decided AND tag != security")
+        );
+
+        assert!(parse_expression("owner=john").is_err());
+        assert!(parse_expression("status=").is_err());
+        assert!(parse_expression("(status=wip").is_err());
+        assert!(parse_expression("status=wip)").is_err());
+        assert!(parse_expression("title=\"not closed").is_err());
+        assert!(parse_expression("status>=decided").is_err());
+        assert!(parse_expression("tag~sec").is_err());
+        assert!(parse_expression("decider<john").is_err());
+        assert!(parse_expression("date~2020").is_err());
+        assert!(parse_expression("status=decidd").is_err());
+        assert!(parse_expression("status=unknown").is_ok());
+        assert!(parse_expression("date>=2020-13-01").is_err());
+        assert!(parse_expression("date<yesterday").is_err());
+        assert!(parse_expression("id<=ten").is_err());
+        assert!(parse_expression("id=-1").is_err());
+        assert!(parse_expression("date>=2020-01-01 and id<=0010").is_ok());
+        assert_eq!(
+            Err(String::from("Invalid date [2020-1] - expected YYYY-MM-DD")),
+            parse_expression("date=2020-1")
+        );
+    }
+}
@@ -0,0 +1,1049 @@
+extern crate slog;
+use slog::*;
+
+use std::collections::HashMap;
+use std::fs::{self};
+use std::io::{self};
+use std::path::{Path, PathBuf};
+
+extern crate adr_config;
+
+use chrono::prelude::*;
+use git2::{
+    Delta, DiffFindOptions, DiffOptions, Index, IndexEntry, IndexTime, ObjectType, Repository,
+    Sort, Tree, TreeWalkMode, TreeWalkResult,
+};
+
+use crate::adr_discovery::AdrDiscovery;
+use crate::adr_repo::*;
+
+fn get_logger() -> slog::Logger {
+    adr_config::logger::get_logger()
+}
+
+fn to_io_error(why: git2::Error) -> io::Error {
+    io::Error::other(why.message())
+}
+
+/// Opens the git repository `path` belongs to and returns it with its (canonical) working directory
+fn discover(path: &Path) -> io::Result<(Repository, PathBuf)> {
+    let repo = Repository::discover(path).map_err(to_io_error)?;
+    let workdir = match repo.workdir() {
+        Some(workdir) => fs::canonicalize(workdir)?,
+        None => return Err(io::Error::other("Unable to work with a bare repository")),
+    };
+
+    Ok((repo, workdir))
+}
+
+/// Returns the path of `path` (which may not exist anymore) in the working directory `workdir`
+fn relative_to_workdir(path: &Path, workdir: &Path) -> io::Result<PathBuf> {
+    let path = match fs::canonicalize(path) {
+        Ok(path) => path,
+        //the file may have been deleted since, so look for its directory
+        Err(_why) => match (path.parent(), path.file_name()) {
+            (Some(parent), Some(name)) => {
+                let parent = match parent == Path::new("") {
+                    true => Path::new("."),
+                    false => parent,
+                };
+                fs::canonicalize(parent)?.join(name)
+            }
+            _ => path.to_path_buf(),
+        },
+    };
+    match path.strip_prefix(workdir) {
+        Ok(relative_path) => Ok(relative_path.to_path_buf()),
+        Err(_why) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "[{}] is not part of the repository [{}]",
+                path.display(),
+                workdir.display()
+            ),
+        )),
+    }
+}
+
+/// Returns the tree of the revision, e.g. `v3.2`, `master~2` or a commit id
+fn rev_tree<'a>(repo: &'a Repository, rev: &str) -> io::Result<Tree<'a>> {
+    match repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_tree())
+    {
+        Ok(tree) => Ok(tree),
+        Err(why) => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Unknown revision [{}] - {}", rev, why.message()),
+        )),
+    }
+}
+
+/// Lists the ADRs of `dir` as they were at the git revision `rev` (e.g. a tag, a branch or a commit id). Unlike
+/// `adr_repo::list_all_adr`, the files are read from the tree of the revision, not from the working directory.
+///
+/// # Arguments
+///
+/// * `dir` - the directory of the ADRs (typically `AdrToolConfig.adr_src_dir`), which must be part of a git checkout
+/// * `rev` - the revision, e.g. `v3.2`
+///
+pub fn list_all_adr_at_rev(dir: &Path, rev: &str) -> io::Result<Vec<Adr>> {
+    let (repo, workdir) = discover(dir)?;
+    let relative_dir = relative_to_workdir(dir, &workdir)?;
+    let tree = rev_tree(&repo, rev)?;
+    list_all_adr_in_tree(&repo, tree, dir, &relative_dir, rev)
+}
+
+/// Lists the ADRs of `dir` as they are staged in the git index, i.e. as they would be committed. The index is the one
+/// of `GIT_INDEX_FILE` if set, as git does when running the hooks of `git commit -a` or `git commit <paths>`.
+///
+/// # Arguments
+///
+/// * `dir` - the directory of the ADRs (typically `AdrToolConfig.adr_src_dir`), which must be part of a git checkout
+///
+pub fn list_all_adr_staged(dir: &Path) -> io::Result<Vec<Adr>> {
+    let (repo, workdir) = discover(dir)?;
+    let relative_dir = relative_to_workdir(dir, &workdir)?;
+    let mut index = match std::env::var_os("GIT_INDEX_FILE") {
+        Some(path) => Index::open(Path::new(&path)).map_err(to_io_error)?,
+        None => repo.index().map_err(to_io_error)?,
+    };
+    let tree_id = index.write_tree_to(&repo).map_err(to_io_error)?;
+    let tree = repo.find_tree(tree_id).map_err(to_io_error)?;
+    list_all_adr_in_tree(&repo, tree, dir, &relative_dir, "index")
+}
+
+/// Lists the ADRs of `dir` in the tree of a revision (or of the index)
+fn list_all_adr_in_tree(
+    repo: &Repository,
+    tree: Tree,
+    dir: &Path,
+    relative_dir: &Path,
+    rev: &str,
+) -> io::Result<Vec<Adr>> {
+    let tree = match relative_dir == Path::new("") {
+        true => tree,
+        false => match tree.get_path(relative_dir) {
+            Ok(entry) if entry.kind() == Some(ObjectType::Tree) => entry
+                .to_object(repo)
+                .and_then(|object| object.peel_to_tree())
+                .map_err(to_io_error)?,
+            _ => {
+                debug!(
+                    get_logger(),
+                    "[{}] does not exist at revision [{}]",
+                    relative_dir.display(),
+                    rev
+                );
+                return Ok(Vec::new());
+            }
+        },
+    };
+
+    //same files as the ones of list_all_adr, with the rules of the working directory (e.g. its .adrignore)
+    let discovery = AdrDiscovery::new(&adr_config::config::get_config(), dir);
+    let mut files = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+        let file_path = format!("{}{}", root, entry.name().unwrap_or_default());
+        let is_dir = entry.kind() == Some(ObjectType::Tree);
+        if !discovery.is_adr(&dir.join(&file_path), is_dir) {
+            return TreeWalkResult::Skip;
+        }
+        if entry.kind() == Some(ObjectType::Blob) {
+            files.push((file_path, entry.id()));
+        }
+        TreeWalkResult::Ok
+    })
+    .map_err(to_io_error)?;
+
+    let mut adrs = Vec::new();
+    for (file_path, id) in files {
+        debug!(get_logger(), "got file [{}] at [{}]", file_path, rev);
+        let blob = repo.find_blob(id).map_err(to_io_error)?;
+        adrs.push(Adr::from(
+            format!("{}", dir.display()),
+            file_path,
+            String::from_utf8_lossy(blob.content()).to_string(),
+        ));
+    }
+    adrs.sort_by_key(|adr| adr.file_id);
+
+    Ok(adrs)
+}
+
+/// Builds the ADR `full_path` as it was at the git revision `rev`, cf. `adr_repo::build_adr`
+pub fn build_adr_at_rev(base_path: &Path, full_path: &Path, rev: &str) -> io::Result<Adr> {
+    let (repo, workdir) = discover(base_path)?;
+    let relative_path = relative_to_workdir(full_path, &workdir)?;
+    let tree = rev_tree(&repo, rev)?;
+    let blob = match tree.get_path(&relative_path) {
+        Ok(entry) => repo.find_blob(entry.id()).map_err(to_io_error)?,
+        Err(_why) => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!(
+                    "[{}] does not exist at revision [{}]",
+                    relative_path.display(),
+                    rev
+                ),
+            ))
+        }
+    };
+
+    let splitted_file_path = split_path(base_path, full_path);
+    Ok(Adr::from(
+        format!("{}", splitted_file_path.0.display()),
+        format!("{}", splitted_file_path.1.display()),
+        String::from_utf8_lossy(blob.content()).to_string(),
+    ))
+}
+
+/// Returns the ID of the ADR as written in its file name (e.g. `0012` for `0012-my-decision.adoc`), or the file name
+/// without extension if the ADR has no ID prefix.
+pub fn adr_id(path: &Path) -> String {
+    let name = path
+        .file_stem()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let id: String = name.chars().take_while(|c| c.is_ascii_digit()).collect();
+    match id.is_empty() {
+        true => name,
+        false => id,
+    }
+}
+
+/// Returns the commit message of a lifecycle change, e.g. `adr: 0012 decided`
+pub fn commit_message(path: &Path, action: &str) -> String {
+    format!("adr: {} {}", adr_id(path), action)
+}
+
+/// Returns the commit message of a lifecycle change between two ADRs, e.g. `adr: 0015 supersedes 0012`
+pub fn commit_message_by(by_path: &Path, action: &str, path: &Path) -> String {
+    format!("adr: {} {} {}", adr_id(by_path), action, adr_id(path))
+}
+
+/// Stages the given files in the git repository they belong to and commits them (and only them, whatever has already
+/// been staged) on `HEAD`. Returns the id of the new commit.
+///
+/// # Arguments
+///
+/// * `paths` - the files to commit, which must belong to the same repository
+/// * `message` - the commit message, cf. `commit_message`
+///
+pub fn commit_files(paths: &[&Path], message: &str) -> io::Result<String> {
+    if paths.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Nothing to commit",
+        ));
+    }
+    let paths: Vec<PathBuf> = paths
+        .iter()
+        .map(fs::canonicalize)
+        .collect::<io::Result<Vec<PathBuf>>>()?;
+
+    let (repo, workdir) = discover(paths[0].parent().unwrap())?;
+    debug!(
+        get_logger(),
+        "Want to commit [{:?}] in [{}]",
+        paths,
+        workdir.display()
+    );
+
+    let parent = match repo.head() {
+        Ok(head) => Some(head.peel_to_commit().map_err(to_io_error)?),
+        Err(_why) => None, //unborn HEAD, i.e. first commit
+    };
+
+    //the tree of the commit is the one of HEAD, plus the given files
+    let mut tree_index = Index::new().map_err(to_io_error)?;
+    if let Some(parent) = &parent {
+        tree_index
+            .read_tree(&parent.tree().map_err(to_io_error)?)
+            .map_err(to_io_error)?;
+    }
+    let mut index = repo.index().map_err(to_io_error)?;
+    for path in paths.iter() {
+        let relative_path = relative_to_workdir(path, &workdir)?;
+        index.add_path(&relative_path).map_err(to_io_error)?;
+        let blob = repo.blob_path(path).map_err(to_io_error)?;
+        tree_index
+            .add(&IndexEntry {
+                ctime: IndexTime::new(0, 0),
+                mtime: IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode: 0o100644,
+                uid: 0,
+                gid: 0,
+                file_size: 0,
+                id: blob,
+                flags: 0,
+                flags_extended: 0,
+                path: relative_path
+                    .to_string_lossy()
+                    .replace('\\', "/")
+                    .into_bytes(),
+            })
+            .map_err(to_io_error)?;
+    }
+    index.write().map_err(to_io_error)?;
+
+    let tree_id = tree_index.write_tree_to(&repo).map_err(to_io_error)?;
+    let tree = repo.find_tree(tree_id).map_err(to_io_error)?;
+    let signature = repo.signature().map_err(to_io_error)?;
+    let parents = match &parent {
+        Some(parent) => vec![parent],
+        None => vec![],
+    };
+    let commit_id = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .map_err(to_io_error)?;
+    info!(get_logger(), "Committed [{}] - [{}]", commit_id, message);
+
+    Ok(commit_id.to_string())
+}
+
+/// The name of the ADR merge driver in the git configuration and `.gitattributes`
+pub const MERGE_DRIVER: &str = "adr";
+
+/// Registers the ADR merge driver (cf. `adr_merge::merge_adr_files`) in the git repository `dir` belongs to, i.e.
+/// `merge.adr.driver` in the repository configuration and e.g. `*.adoc merge=adr` for each extension in the
+/// `.gitattributes` of `dir`. Returns `false` if it was already registered.
+///
+/// # Arguments
+///
+/// * `dir` - the directory of the ADRs, cf. `AdrToolConfig.adr_src_dir`
+/// * `command` - the command git has to run, e.g. `adr merge-driver %O %A %B`
+/// * `extensions` - the extensions of the ADRs, e.g. `adoc`, cf. `AdrToolConfig.adr_extensions`
+///
+pub fn install_merge_driver(dir: &Path, command: &str, extensions: &[String]) -> io::Result<bool> {
+    let (repo, workdir) = discover(dir)?;
+    let mut config = repo.config().map_err(to_io_error)?;
+    let driver_key = format!("merge.{}.driver", MERGE_DRIVER);
+    let has_driver = config.get_string(&driver_key).ok() == Some(command.to_string());
+    if !has_driver {
+        config
+            .set_str(&format!("merge.{}.name", MERGE_DRIVER), "ADR merge driver")
+            .map_err(to_io_error)?;
+        config.set_str(&driver_key, command).map_err(to_io_error)?;
+        info!(
+            get_logger(),
+            "Merge driver [{}] registered in [{}]",
+            command,
+            workdir.display()
+        );
+    }
+
+    let attributes_path = dir.join(".gitattributes");
+    let mut attributes = fs::read_to_string(&attributes_path).unwrap_or_default();
+    let mut has_attributes = true;
+    for extension in extensions {
+        let attribute = format!("*.{} merge={}", extension, MERGE_DRIVER);
+        if attributes.lines().any(|line| line.trim() == attribute) {
+            continue;
+        }
+        if !attributes.is_empty() && !attributes.ends_with('\n') {
+            attributes.push('\n');
+        }
+        attributes.push_str(&format!("{}\n", attribute));
+        has_attributes = false;
+        info!(
+            get_logger(),
+            "[{}] added to [{}]",
+            attribute,
+            attributes_path.display()
+        );
+    }
+    if !has_attributes {
+        fs::write(&attributes_path, attributes)?;
+    }
+
+    Ok(!(has_driver && has_attributes))
+}
+
+/// The first lines of the pre-commit hook written by `install_pre_commit_hook`
+const PRE_COMMIT_HOOK_HEADER: &str = "#!/bin/sh\n# adr pre-commit hook - cf. adr hook install\n";
+
+/// Writes the pre-commit hook of the git repository `dir` belongs to (in `core.hooksPath` if set), so that `command`
+/// checks the staged ADRs before each commit. A pre-commit hook which has not been written by this function is only
+/// replaced if `force` is set. Returns `false` if the hook was already installed.
+///
+/// # Arguments
+///
+/// * `dir` - the directory of the ADRs, cf. `AdrToolConfig.adr_src_dir`
+/// * `command` - the command the hook has to run, e.g. `adr lint --staged`
+/// * `force` - replace an existing pre-commit hook
+///
+pub fn install_pre_commit_hook(dir: &Path, command: &str, force: bool) -> io::Result<bool> {
+    let (repo, workdir) = discover(dir)?;
+    let hooks_dir = match repo
+        .config()
+        .and_then(|config| config.get_path("core.hooksPath"))
+    {
+        Ok(hooks_dir) => workdir.join(hooks_dir),
+        Err(_why) => repo.path().join("hooks"),
+    };
+    let hook_path = hooks_dir.join("pre-commit");
+    let hook = format!("{}exec {}\n", PRE_COMMIT_HOOK_HEADER, command);
+
+    if let Ok(existing_hook) = fs::read_to_string(&hook_path) {
+        if existing_hook == hook {
+            debug!(
+                get_logger(),
+                "Hook [{}] is already installed",
+                hook_path.display()
+            );
+            return Ok(false);
+        }
+        if !existing_hook.starts_with(PRE_COMMIT_HOOK_HEADER) && !force {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "A pre-commit hook already exists [{}] - use --force to replace it",
+                    hook_path.display()
+                ),
+            ));
+        }
+    }
+
+    fs::create_dir_all(&hooks_dir)?;
+    fs::write(&hook_path, hook)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755))?;
+    }
+    info!(get_logger(), "Hook [{}] installed", hook_path.display());
+
+    Ok(true)
+}
+
+/// Sets `Adr.git_history` of the ADRs whose file is committed in a git repository, i.e. their creation and last
+/// modification dates, their authors and the date of the first commit of each of their status. The history of all
+/// the ADRs is computed by walking the commits once and diffing each of them with its parent, restricted to the
+/// directories of the ADRs. The history of a renamed ADR includes the one of its former file. The merge commits are
+/// skipped (the commits they bring are walked on their own), so the changes made by a merge itself (e.g. a resolved
+/// conflict) are ignored. ADRs which are not part of a git checkout are left unchanged.
+pub fn load_git_history(adrs: &mut [Adr]) -> io::Result<()> {
+    let paths: Vec<Option<PathBuf>> = adrs
+        .iter()
+        .map(|adr| fs::canonicalize(adr.path()).ok())
+        .collect();
+    let first_path = match paths.iter().flatten().next() {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let (repo, workdir) = match discover(first_path.parent().unwrap()) {
+        Ok(repo) => repo,
+        Err(why) => {
+            debug!(get_logger(), "No git history for the ADRs - [{}]", why);
+            return Ok(());
+        }
+    };
+    //the path of each ADR in the repository
+    let relative_paths: Vec<Option<PathBuf>> = paths
+        .iter()
+        .map(|path| {
+            path.as_ref()
+                .and_then(|path| path.strip_prefix(&workdir).ok())
+                .map(|path| path.to_path_buf())
+        })
+        .collect();
+
+    let mut revwalk = repo.revwalk().map_err(to_io_error)?;
+    if revwalk.push_head().is_err() {
+        debug!(get_logger(), "No commit yet in [{}]", workdir.display());
+        return Ok(());
+    }
+    revwalk
+        .set_sorting(Sort::TOPOLOGICAL | Sort::TIME | Sort::REVERSE)
+        .map_err(to_io_error)?;
+
+    let mut diff_options = DiffOptions::new();
+    for dir in get_relative_dirs(adrs, &workdir) {
+        diff_options.pathspec(dir);
+    }
+    let mut find_options = DiffFindOptions::new();
+    find_options.renames(true);
+
+    //the history of each file, by its path in the repository as of the walked commit
+    let mut histories: HashMap<PathBuf, AdrGitHistory> = HashMap::new();
+    for oid in revwalk {
+        let commit = repo
+            .find_commit(oid.map_err(to_io_error)?)
+            .map_err(to_io_error)?;
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        let tree = commit.tree().map_err(to_io_error)?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree().map_err(to_io_error)?),
+            Err(_why) => None,
+        };
+        let mut diff = repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut diff_options))
+            .map_err(to_io_error)?;
+        diff.find_similar(Some(&mut find_options))
+            .map_err(to_io_error)?;
+
+        let date = match Utc
+            .timestamp_opt(commit.author().when().seconds(), 0)
+            .single()
+        {
+            Some(date) => date.format("%Y-%m-%d").to_string(),
+            None => {
+                debug!(
+                    get_logger(),
+                    "Skipping commit [{}] - invalid date",
+                    commit.id()
+                );
+                continue;
+            }
+        };
+        let author = commit.author().name().unwrap_or_default().to_string();
+
+        for delta in diff.deltas() {
+            let old_path = delta.old_file().path().map(|path| path.to_path_buf());
+            let new_path = match delta.new_file().path() {
+                Some(path) => path.to_path_buf(),
+                None => continue,
+            };
+            let git_history = match delta.status() {
+                Delta::Deleted => {
+                    histories.remove(&new_path);
+                    continue;
+                }
+                Delta::Renamed => old_path.and_then(|old_path| histories.remove(&old_path)),
+                Delta::Added | Delta::Modified | Delta::Copied | Delta::Typechange => {
+                    histories.remove(&new_path)
+                }
+                _ => continue,
+            };
+            let mut git_history = git_history.unwrap_or_else(|| AdrGitHistory {
+                created_at: date.clone(),
+                ..AdrGitHistory::default()
+            });
+
+            git_history.last_modified = date.clone();
+            if !git_history.authors.contains(&author) {
+                git_history.authors.push(author.clone());
+            }
+            let blob = repo.find_blob(delta.new_file().id()).map_err(to_io_error)?;
+            let content = String::from_utf8_lossy(blob.content()).to_string();
+            let status =
+                Adr::from(String::new(), format!("{}", new_path.display()), content).status;
+            if !git_history
+                .status_dates
+                .iter()
+                .any(|transition| transition.status == status)
+            {
+                git_history.status_dates.push(AdrTransition {
+                    date: date.clone(),
+                    status,
+                });
+            }
+            histories.insert(new_path, git_history);
+        }
+    }
+
+    for (adr, relative_path) in adrs.iter_mut().zip(relative_paths.iter()) {
+        if let Some(git_history) = relative_path
+            .as_ref()
+            .and_then(|relative_path| histories.remove(relative_path))
+        {
+            adr.git_history = Some(git_history);
+        }
+    }
+
+    Ok(())
+}
+
+/// The directories of the ADRs in the repository whose working directory is `workdir`, none meaning the whole
+/// repository
+fn get_relative_dirs(adrs: &[Adr], workdir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for adr in adrs {
+        let dir = match fs::canonicalize(&adr.base_path) {
+            Ok(dir) => dir,
+            Err(_why) => continue,
+        };
+        match dir.strip_prefix(workdir) {
+            Ok(dir) if dir == Path::new("") => return Vec::new(),
+            Ok(dir) if !dirs.iter().any(|val| val == dir) => dirs.push(dir.to_path_buf()),
+            _ => {}
+        }
+    }
+
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate tempdir;
+    use tempdir::TempDir;
+
+    use crate::adr_git::*;
+
+    fn commit(repo: &Repository, path: &Path, content: &str, author: &str, time: i64) {
+        let full_path = repo.workdir().unwrap().join(path);
+        fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+        fs::write(full_path, content).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(path).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature =
+            git2::Signature::new(author, "john.smith@example.com", &git2::Time::new(time, 0))
+                .unwrap();
+        let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+        let parents = match &parent {
+            Some(parent) => vec![parent],
+            None => vec![],
+        };
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "a commit",
+            &tree,
+            &parents,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_load_git_history() {
+        let src = match TempDir::new("my_repo") {
+            Ok(src) => src,
+            Err(why) => {
+                println!("Unable to get src dir [{}]", why);
+                panic!("{}", why);
+            }
+        };
+        let repo = Repository::init(src.path()).unwrap();
+
+        let wip = "= first\n\n*Status:* {wip} *Date:* 2020-01-01";
+        commit(
+            &repo,
+            Path::new("0001-first.adoc"),
+            wip,
+            "John Smith",
+            1577880000,
+        ); //2020-01-01
+        commit(
+            &repo,
+            Path::new("0002-second.adoc"),
+            wip,
+            "Jane Doe",
+            1580558400,
+        ); //2020-02-01
+        let decided = "= first\n\n*Status:* {decided} *Date:* 2020-03-01";
+        commit(
+            &repo,
+            Path::new("0001-first.adoc"),
+            decided,
+            "Jane Doe",
+            1583064000,
+        ); //2020-03-01
+        let decided = "= first\n\n*Status:* {decided} *Date:* 2020-04-01";
+        commit(
+            &repo,
+            Path::new("0001-first.adoc"),
+            decided,
+            "Jane Doe",
+            1585742400,
+        ); //2020-04-01
+        fs::write(src.path().join("0003-third.adoc"), wip).unwrap();
+
+        let mut adrs = list_all_adr(src.path()).unwrap();
+        adrs.sort_by_key(|adr| adr.file_id);
+        load_git_history(&mut adrs).unwrap();
+
+        let git_history = adrs[0].git_history.as_ref().unwrap();
+        assert_eq!("2020-01-01", git_history.created_at);
+        assert_eq!("2020-04-01", git_history.last_modified);
+        assert_eq!(
+            vec![String::from("John Smith"), String::from("Jane Doe")],
+            git_history.authors
+        );
+        assert_eq!(
+            vec![
+                AdrTransition {
+                    date: String::from("2020-01-01"),
+                    status: Status::WIP
+                },
+                AdrTransition {
+                    date: String::from("2020-03-01"),
+                    status: Status::DECIDED
+                }
+            ],
+            git_history.status_dates
+        );
+        assert_eq!(Some(String::from("2020-01-01")), adrs[0].created_at());
+        assert_eq!(
+            Some(String::from("2020-03-01")),
+            adrs[0].status_date(Status::DECIDED)
+        );
+
+        let git_history = adrs[1].git_history.as_ref().unwrap();
+        assert_eq!("2020-02-01", git_history.created_at);
+        assert_eq!(vec![String::from("Jane Doe")], git_history.authors);
+
+        //not committed yet
+        assert_eq!(None, adrs[2].git_history);
+        assert_eq!(None, adrs[2].created_at());
+    }
+
+    #[test]
+    fn test_load_git_history_renamed_and_merged() {
+        let src = match TempDir::new("my_repo") {
+            Ok(src) => src,
+            Err(why) => {
+                println!("Unable to get src dir [{}]", why);
+                panic!("{}", why);
+            }
+        };
+        let repo = Repository::init(src.path()).unwrap();
+        let adr_dir = src.path().join("docs").join("adr");
+
+        let wip = "= first\n\n*Status:* {wip} *Date:* 2020-01-01";
+        commit(
+            &repo,
+            Path::new("docs/adr/0001-first.adoc"),
+            wip,
+            "John Smith",
+            1577880000,
+        ); //2020-01-01
+
+        //renamed by another author
+        fs::rename(
+            adr_dir.join("0001-first.adoc"),
+            adr_dir.join("0001-first-adr.adoc"),
+        )
+        .unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .remove_path(Path::new("docs/adr/0001-first.adoc"))
+            .unwrap();
+        index
+            .add_path(Path::new("docs/adr/0001-first-adr.adoc"))
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::new(
+            "Jane Doe",
+            "jane.doe@example.com",
+            &git2::Time::new(1580558400, 0),
+        )
+        .unwrap(); //2020-02-01
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "a rename",
+            &tree,
+            &[&parent],
+        )
+        .unwrap();
+
+        //a merge commit changing the ADR is skipped
+        let first_commit = parent;
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        let decided = "= first\n\n*Status:* {decided} *Date:* 2020-03-01";
+        fs::write(adr_dir.join("0001-first-adr.adoc"), decided).unwrap();
+        let mut index = repo.index().unwrap();
+        index
+            .add_path(Path::new("docs/adr/0001-first-adr.adoc"))
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::new(
+            "Max Merge",
+            "max.merge@example.com",
+            &git2::Time::new(1583064000, 0),
+        )
+        .unwrap(); //2020-03-01
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "a merge",
+            &tree,
+            &[&parent, &first_commit],
+        )
+        .unwrap();
+
+        let mut adrs = list_all_adr(&adr_dir).unwrap();
+        load_git_history(&mut adrs).unwrap();
+
+        let git_history = adrs[0].git_history.as_ref().unwrap();
+        assert_eq!("2020-01-01", git_history.created_at);
+        assert_eq!("2020-02-01", git_history.last_modified);
+        assert_eq!(
+            vec![String::from("John Smith"), String::from("Jane Doe")],
+            git_history.authors
+        );
+        assert_eq!(
+            vec![AdrTransition {
+                date: String::from("2020-01-01"),
+                status: Status::WIP
+            }],
+            git_history.status_dates
+        );
+    }
+
+    #[test]
+    fn test_list_all_adr_at_rev() {
+        let src = match TempDir::new("my_repo") {
+            Ok(src) => src,
+            Err(why) => {
+                println!("Unable to get src dir [{}]", why);
+                panic!("{}", why);
+            }
+        };
+        let repo = Repository::init(src.path()).unwrap();
+        let adr_dir = src.path().join("docs").join("adr");
+
+        let wip = "= first\n\n*Status:* {wip} *Date:* 2020-01-01";
+        commit(
+            &repo,
+            Path::new("docs/adr/0001-first.adoc"),
+            wip,
+            "John Smith",
+            1577880000,
+        );
+        commit(
+            &repo,
+            Path::new("docs/adr/.index/0009-hidden.adoc"),
+            wip,
+            "John Smith",
+            1577880000,
+        );
+        let v1 = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.tag_lightweight("v1", v1.as_object(), false).unwrap();
+
+        let decided = "= first\n\n*Status:* {decided} *Date:* 2020-03-01";
+        commit(
+            &repo,
+            Path::new("docs/adr/0001-first.adoc"),
+            decided,
+            "Jane Doe",
+            1583064000,
+        );
+        commit(
+            &repo,
+            Path::new("docs/adr/sub/0002-second.adoc"),
+            wip,
+            "Jane Doe",
+            1583064000,
+        );
+        fs::remove_file(adr_dir.join("0001-first.adoc")).unwrap();
+
+        let adrs = list_all_adr_at_rev(&adr_dir, "v1").unwrap();
+        assert_eq!(1, adrs.len());
+        assert_eq!("0001-first.adoc", adrs[0].file_path);
+        assert_eq!(Status::WIP, adrs[0].status);
+
+        let adrs = list_all_adr_at_rev(&adr_dir, "HEAD").unwrap();
+        assert_eq!(2, adrs.len());
+        assert_eq!(Status::DECIDED, adrs[0].status);
+        assert_eq!("sub/0002-second.adoc", adrs[1].file_path);
+        assert_eq!(
+            adrs[1].path(),
+            format!("{}", adr_dir.join("sub/0002-second.adoc").display())
+        );
+
+        //the file does not exist anymore in the working directory
+        let adr = build_adr_at_rev(&adr_dir, &adr_dir.join("0001-first.adoc"), "v1").unwrap();
+        assert_eq!(Status::WIP, adr.status);
+        assert_eq!("0001-first.adoc", adr.file_path);
+
+        let adr = build_adr_at_rev(&adr_dir, &adr_dir.join("sub/0002-second.adoc"), "v1");
+        assert_eq!(io::ErrorKind::NotFound, adr.unwrap_err().kind());
+        let adrs = list_all_adr_at_rev(&adr_dir, "v2");
+        assert_eq!(io::ErrorKind::NotFound, adrs.unwrap_err().kind());
+    }
+
+    #[test]
+    fn test_commit_message() {
+        assert_eq!(
+            "adr: 0012 decided",
+            commit_message(Path::new("src/0012-my-decision.adoc"), "decided")
+        );
+        assert_eq!(
+            "adr: 0015 supersedes 0012",
+            commit_message_by(
+                Path::new("0015-new-decision.adoc"),
+                "supersedes",
+                Path::new("sub/0012-my-decision.adoc")
+            )
+        );
+        assert_eq!(
+            "adr: my-decision new",
+            commit_message(Path::new("my-decision.adoc"), "new")
+        );
+    }
+
+    #[test]
+    fn test_commit_files() {
+        let src = match TempDir::new("my_repo") {
+            Ok(src) => src,
+            Err(why) => {
+                println!("Unable to get src dir [{}]", why);
+                panic!("{}", why);
+            }
+        };
+        let repo = Repository::init(src.path()).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "John Smith").unwrap();
+        config
+            .set_str("user.email", "john.smith@example.com")
+            .unwrap();
+
+        fs::create_dir_all(src.path().join("src")).unwrap();
+        let adr_1 = src.path().join("src").join("0001-first.adoc");
+        let adr_2 = src.path().join("src").join("0002-second.adoc");
+        let other = src.path().join("README.md");
+        fs::write(&adr_1, "= first").unwrap();
+        fs::write(&adr_2, "= second").unwrap();
+        fs::write(&other, "readme").unwrap();
+
+        commit_files(&[adr_1.as_path()], "adr: 0001 new").unwrap();
+        fs::write(&adr_1, "= first (superseded)").unwrap();
+        //already staged files are left staged
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        commit_files(
+            &[adr_2.as_path(), adr_1.as_path()],
+            "adr: 0002 supersedes 0001",
+        )
+        .unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!("adr: 0002 supersedes 0001", head.message().unwrap());
+        assert_eq!(1, head.parent_count());
+        let tree = head.tree().unwrap();
+        assert!(tree.get_path(Path::new("src/0001-first.adoc")).is_ok());
+        assert!(tree.get_path(Path::new("src/0002-second.adoc")).is_ok());
+        //what is not part of the ADRs is not committed
+        assert!(tree.get_path(Path::new("README.md")).is_err());
+        assert!(repo
+            .index()
+            .unwrap()
+            .get_path(Path::new("README.md"), 0)
+            .is_some());
+
+        let outside = TempDir::new("not_a_repo").unwrap();
+        let file = outside.path().join("0003-third.adoc");
+        fs::write(&file, "= third").unwrap();
+        assert!(commit_files(&[file.as_path()], "adr: 0003 new").is_err());
+    }
+
+    #[test]
+    fn test_list_all_adr_staged() {
+        let src = match TempDir::new("my_repo") {
+            Ok(src) => src,
+            Err(why) => {
+                println!("Unable to get src dir [{}]", why);
+                panic!("{}", why);
+            }
+        };
+        let repo = Repository::init(src.path()).unwrap();
+        let dir = src.path().join("src");
+        commit(
+            &repo,
+            Path::new("src/0001-first.adoc"),
+            "= first\n\n*Status:* {wip} *Date:* 2020-01-01",
+            "John Smith",
+            1577869200,
+        );
+        fs::write(
+            dir.join("0001-first.adoc"),
+            "= first\n\n*Status:* {decided} *Date:* 2020-01-02",
+        )
+        .unwrap();
+        fs::write(dir.join("0002-second.adoc"), "= second").unwrap();
+        fs::write(dir.join("0003-third.adoc"), "= third").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("src/0002-second.adoc")).unwrap();
+        index.write().unwrap();
+
+        //only the staged changes are listed
+        let adrs = list_all_adr_staged(&dir).unwrap();
+        assert_eq!(2, adrs.len());
+        assert_eq!(Status::WIP, adrs[0].status);
+        assert_eq!("second", adrs[1].title);
+    }
+
+    #[test]
+    fn test_install_pre_commit_hook() {
+        let src = match TempDir::new("my_repo") {
+            Ok(src) => src,
+            Err(why) => {
+                println!("Unable to get src dir [{}]", why);
+                panic!("{}", why);
+            }
+        };
+        let repo = Repository::init(src.path()).unwrap();
+        let hook_path = repo.path().join("hooks").join("pre-commit");
+
+        assert!(install_pre_commit_hook(src.path(), "adr lint --staged", false).unwrap());
+        assert!(!install_pre_commit_hook(src.path(), "adr lint --staged", false).unwrap());
+        assert_eq!(
+            "#!/bin/sh\n# adr pre-commit hook - cf. adr hook install\nexec adr lint --staged\n",
+            fs::read_to_string(&hook_path).unwrap()
+        );
+
+        //another hook is only replaced if forced
+        fs::write(&hook_path, "#!/bin/sh\nmake test\n").unwrap();
+        assert!(install_pre_commit_hook(src.path(), "adr lint --staged", false).is_err());
+        assert!(install_pre_commit_hook(src.path(), "adr lint --staged", true).unwrap());
+    }
+
+    #[test]
+    fn test_install_merge_driver() {
+        let src = match TempDir::new("my_repo") {
+            Ok(src) => src,
+            Err(why) => {
+                println!("Unable to get src dir [{}]", why);
+                panic!("{}", why);
+            }
+        };
+        let repo = Repository::init(src.path()).unwrap();
+        let dir = src.path().join("src");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(".gitattributes"), "*.png binary").unwrap();
+
+        let extensions = vec![String::from("adoc")];
+        assert!(install_merge_driver(&dir, "adr merge-driver %O %A %B", &extensions).unwrap());
+        assert!(!install_merge_driver(&dir, "adr merge-driver %O %A %B", &extensions).unwrap());
+
+        let config = repo.config().unwrap().snapshot().unwrap();
+        assert_eq!(
+            "adr merge-driver %O %A %B",
+            config.get_str("merge.adr.driver").unwrap()
+        );
+        assert_eq!(
+            "*.png binary\n*.adoc merge=adr\n",
+            fs::read_to_string(dir.join(".gitattributes")).unwrap()
+        );
+
+        //a new extension
+        let extensions = vec![String::from("adoc"), String::from("md")];
+        assert!(install_merge_driver(&dir, "adr merge-driver %O %A %B", &extensions).unwrap());
+        assert_eq!(
+            "*.png binary\n*.adoc merge=adr\n*.md merge=adr\n",
+            fs::read_to_string(dir.join(".gitattributes")).unwrap()
+        );
+    }
+}
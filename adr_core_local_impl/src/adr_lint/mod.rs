@@ -0,0 +1,428 @@
+extern crate slog;
+use slog::*;
+
+use std::collections::HashMap;
+use std::io::{self};
+use std::path::Path;
+
+extern crate adr_config;
+use adr_config::config::AdrToolConfig;
+
+use crate::adr_federation::{list_all_federated_adr, parse_qualified_id, resolve_reference};
+use crate::adr_git::{list_all_adr_at_rev, list_all_adr_staged};
+use crate::adr_repo::*;
+
+fn get_logger() -> slog::Logger {
+    adr_config::logger::get_logger()
+}
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum LintLevel {
+    WARNING,
+    ERROR,
+}
+
+impl LintLevel {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            LintLevel::WARNING => "warning",
+            LintLevel::ERROR => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LintIssue {
+    pub level: LintLevel,
+    pub path: String,
+    pub message: String,
+}
+
+impl LintIssue {
+    fn warning(adr: &Adr, message: String) -> LintIssue {
+        LintIssue {
+            level: LintLevel::WARNING,
+            path: adr.path(),
+            message,
+        }
+    }
+
+    fn error(adr: &Adr, message: String) -> LintIssue {
+        LintIssue {
+            level: LintLevel::ERROR,
+            path: adr.path(),
+            message,
+        }
+    }
+}
+
+/// Lints all the ADRs available in `AdrToolConfig.adr_src_dir`.
+pub fn lint_all_adr(cfg: &AdrToolConfig) -> io::Result<Vec<LintIssue>> {
+    let adrs = list_all_adr(Path::new(&cfg.adr_src_dir))?;
+    let to_lint: Vec<&Adr> = adrs.iter().collect();
+
+    Ok(lint_adrs(cfg, &adrs, &to_lint))
+}
+
+/// Lints the ADRs of `AdrToolConfig.adr_src_dir` which are staged in git compared to `HEAD`, cf. `lint_changes`.
+pub fn lint_staged_adr(cfg: &AdrToolConfig) -> io::Result<Vec<LintIssue>> {
+    let dir = Path::new(&cfg.adr_src_dir);
+    let previous = match list_all_adr_at_rev(dir, "HEAD") {
+        Ok(adrs) => adrs,
+        //unborn HEAD, i.e. first commit
+        Err(ref why) if why.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(why) => return Err(why),
+    };
+    let staged = list_all_adr_staged(dir)?;
+
+    Ok(lint_changes(cfg, &previous, &staged))
+}
+
+/// Lints the ADRs of `adrs` which have changed compared to `previous` (e.g. the staged ADRs compared to the ones of
+/// `HEAD`). On top of `lint_adrs`, the status changes are checked against the state machine (cf. `AdrState`), and the
+/// references of the unchanged ADRs to the ADRs which have been removed or renamed are broken.
+///
+/// # Arguments
+///
+/// * `cfg` - the configuration, cf. `lint_adr`
+/// * `previous` - the ADRs before the changes
+/// * `adrs` - all the ADRs after the changes
+///
+pub fn lint_changes(cfg: &AdrToolConfig, previous: &[Adr], adrs: &[Adr]) -> Vec<LintIssue> {
+    let previous: HashMap<&str, &Adr> = previous
+        .iter()
+        .map(|adr| (adr.file_path.as_str(), adr))
+        .collect();
+
+    let mut changed = Vec::new();
+    let mut unchanged = Vec::new();
+    let mut status_issues = Vec::new();
+    for adr in adrs.iter() {
+        match previous.get(adr.file_path.as_str()) {
+            Some(old) if old.content == adr.content => {
+                unchanged.push(adr);
+                continue;
+            }
+            Some(old)
+                if old.status != Status::NONE
+                    && old.status != adr.status
+                    && !old.status.can_transition_to(adr.status) =>
+            {
+                status_issues.push(LintIssue::error(
+                    adr,
+                    format!(
+                        "Illegal status change from [{}] to [{}]",
+                        old.status.as_str(),
+                        adr.status.as_str()
+                    ),
+                ))
+            }
+            _ => (),
+        }
+        changed.push(adr);
+    }
+    debug!(get_logger(), "Got [{}] changed ADR(s)", changed.len());
+
+    let mut issues = lint_adrs(cfg, adrs, &changed);
+    issues.append(&mut status_issues);
+
+    //the unchanged ADRs may reference the ADRs which have been removed or renamed
+    let removed: Vec<&str> = previous
+        .values()
+        .filter(|old| !adrs.iter().any(|adr| adr.file_name == old.file_name))
+        .map(|old| old.file_name.as_str())
+        .collect();
+    for adr in unchanged {
+        for reference in adr.references().iter() {
+            if removed.contains(&reference.as_str()) {
+                issues.push(LintIssue::error(
+                    adr,
+                    format!("Broken reference [{}]", reference),
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+/// Lints the ADRs `to_lint` (cf. `lint_adr`) and checks their references and IDs against all the ADRs `adrs`, i.e.
+/// the ADRs they reference must exist and their IDs must be unique. The references to the ADRs of the workspaces
+/// (e.g. `platform:0012`) are checked against `AdrToolConfig.workspaces`, unless the ADRs of the workspaces cannot be
+/// listed (which is reported once, as a warning).
+pub fn lint_adrs(cfg: &AdrToolConfig, adrs: &[Adr], to_lint: &[&Adr]) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let first_federated = to_lint.iter().find(|adr| {
+        adr.references()
+            .iter()
+            .any(|reference| parse_qualified_id(reference).is_some())
+    });
+    let federated = match first_federated {
+        Some(first_federated) => match list_all_federated_adr(&cfg.workspaces) {
+            Ok(federated) => Some(federated),
+            Err(why) => {
+                issues.push(LintIssue::warning(
+                    first_federated,
+                    format!("Unable to check the references to the workspaces - {}", why),
+                ));
+                None
+            }
+        },
+        None => Some(Vec::new()),
+    };
+
+    for adr in to_lint.iter() {
+        issues.append(&mut lint_adr(cfg, adr));
+
+        //the references are the file names of the ADRs (cf. transition_to) or qualified IDs (cf. transition_across)
+        for reference in adr.references().iter() {
+            let is_broken = match parse_qualified_id(reference) {
+                Some(_) => match &federated {
+                    Some(federated) => resolve_reference(federated, adr, reference).is_none(),
+                    None => false,
+                },
+                None => {
+                    cfg.adr_extensions
+                        .iter()
+                        .any(|extension| reference.ends_with(&format!(".{}", extension)))
+                        && !adrs.iter().any(|other| &other.file_name == reference)
+                }
+            };
+            if is_broken {
+                issues.push(LintIssue::error(
+                    adr,
+                    format!("Broken reference [{}]", reference),
+                ));
+            }
+        }
+
+        if adr.file_id == 0 {
+            continue;
+        }
+        for other in adrs.iter() {
+            if other.file_id == adr.file_id && other.file_path != adr.file_path {
+                issues.push(LintIssue::error(
+                    adr,
+                    format!(
+                        "Duplicate id [{}] - also used by [{}]",
+                        adr.file_id,
+                        other.path()
+                    ),
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+/// Lints a single ADR: its status must be known and its tags are checked against the controlled vocabulary (unknown tags are errors, aliases warnings)
+/// (cf. `AdrToolConfig.allowed_tags`).
+pub fn lint_adr(cfg: &AdrToolConfig, adr: &Adr) -> Vec<LintIssue> {
+    debug!(get_logger(), "Want to lint ADR [{}]", adr.path());
+
+    let mut issues = Vec::new();
+    if adr.status == Status::NONE {
+        issues.push(LintIssue::error(
+            adr,
+            String::from("Missing or unknown status"),
+        ));
+    }
+    for tag in adr.tags_array.iter() {
+        match validate_tag(&cfg.allowed_tags, tag) {
+            TagValidation::KNOWN => (),
+            TagValidation::ALIAS(name) => issues.push(LintIssue::warning(
+                adr,
+                format!("Tag [{}] is an alias of [{}]", tag.trim(), name),
+            )),
+            TagValidation::UNKNOWN(Some(name)) => issues.push(LintIssue::error(
+                adr,
+                format!("Unknown tag [{}] - did you mean [{}]?", tag.trim(), name),
+            )),
+            TagValidation::UNKNOWN(None) => issues.push(LintIssue::error(
+                adr,
+                format!("Unknown tag [{}]", tag.trim()),
+            )),
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adr_lint::*;
+    use adr_config::config::TagDefinition;
+    use git2::Repository;
+    use std::fs;
+    use tempdir::TempDir;
+
+    const ADOC_TMPL_TAG: &str = "
+    = short title of solved problem and solution
+
+    *Status:* {wip} *Date:* 2019-10-28
+
+    [tags]#security# [tags]#sec# [tags]#netwrok# [tags]#blockchain#
+    ...";
+
+    #[test]
+    fn test_lint_adr_tags() {
+        let adr = Adr::from(
+            "base_path".to_string(),
+            "a_path".to_string(),
+            ADOC_TMPL_TAG.to_string(),
+        );
+
+        let mut cfg = AdrToolConfig::default();
+        let issues = lint_adr(&cfg, &adr);
+        assert_eq!(0, issues.len());
+
+        cfg.allowed_tags = vec![
+            TagDefinition {
+                name: String::from("security"),
+                description: String::from(""),
+                aliases: vec![String::from("sec")],
+            },
+            TagDefinition {
+                name: String::from("network"),
+                description: String::from(""),
+                aliases: vec![],
+            },
+        ];
+        let issues = lint_adr(&cfg, &adr);
+        assert_eq!(3, issues.len());
+        assert_eq!(LintLevel::WARNING, issues[0].level);
+        assert_eq!("Tag [sec] is an alias of [security]", issues[0].message);
+        assert_eq!(LintLevel::ERROR, issues[1].level);
+        assert_eq!(
+            "Unknown tag [netwrok] - did you mean [network]?",
+            issues[1].message
+        );
+        assert_eq!("Unknown tag [blockchain]", issues[2].message);
+    }
+
+    fn adr(file_path: &str, status: &str) -> Adr {
+        Adr::from(
+            String::from("/adr"),
+            String::from(file_path),
+            format!("= title\n\n*Status:* {} *Date:* 2020-01-01\n", status),
+        )
+    }
+
+    #[test]
+    fn test_lint_changes() {
+        let cfg = AdrToolConfig::default();
+        let previous = vec![
+            adr("0001-a.adoc", "{decided}"),
+            adr("0002-b.adoc", "{decided}"),
+            adr("0003-c.adoc", "{decided} 0009-z.adoc"),
+        ];
+        let adrs = vec![
+            adr("0001-a.adoc", "{wip}"),
+            adr("0002-b.adoc", "{superseded} 0004-d.adoc"),
+            adr("0003-c.adoc", "{decided} 0009-z.adoc"),
+            adr("0004-d.adoc", "{supersedes} 0002-b.adoc 0008-y.adoc"),
+            adr("0004-e.adoc", "none"),
+        ];
+
+        //unchanged ADRs are not linted (0003 and its broken reference)
+        let issues = lint_changes(&cfg, &previous, &adrs);
+        let messages: Vec<&str> = issues.iter().map(|issue| issue.message.as_str()).collect();
+        assert_eq!(
+            vec![
+                "Broken reference [0008-y.adoc]",
+                "Duplicate id [4] - also used by [/adr/0004-e.adoc]",
+                "Missing or unknown status",
+                "Duplicate id [4] - also used by [/adr/0004-d.adoc]",
+                "Illegal status change from [decided] to [wip]",
+            ],
+            messages
+        );
+        assert!(issues.iter().all(|issue| issue.level == LintLevel::ERROR));
+        assert_eq!("/adr/0001-a.adoc", issues[4].path);
+    }
+
+    #[test]
+    fn test_lint_adrs_without_workspace() {
+        let cfg = AdrToolConfig::default();
+        let adrs = vec![
+            adr("0001-a.adoc", "{decided} platform:0012"),
+            adr("0002-b.adoc", "{decided} platform:0013"),
+        ];
+        let to_lint: Vec<&Adr> = adrs.iter().collect();
+
+        //the references to the workspaces are not reported as broken
+        let issues = lint_adrs(&cfg, &adrs, &to_lint);
+        assert_eq!(1, issues.len());
+        assert_eq!(LintLevel::WARNING, issues[0].level);
+        assert_eq!(
+            "Unable to check the references to the workspaces - No workspace - cf. adr workspace add",
+            issues[0].message
+        );
+        assert_eq!("/adr/0001-a.adoc", issues[0].path);
+    }
+
+    #[test]
+    fn test_lint_adrs_extensions() {
+        let mut cfg = AdrToolConfig::default();
+        let adrs = vec![
+            adr("0001-a.md", "{decided} 0002-b.md"),
+            adr("0003-c.md", "{decided} 0001-a.md"),
+        ];
+        let to_lint: Vec<&Adr> = adrs.iter().collect();
+
+        //not an ADR as long as md is not one of the extensions
+        assert_eq!(0, lint_adrs(&cfg, &adrs, &to_lint).len());
+
+        cfg.adr_extensions = vec![String::from("adoc"), String::from("md")];
+        let issues = lint_adrs(&cfg, &adrs, &to_lint);
+        assert_eq!(1, issues.len());
+        assert_eq!("Broken reference [0002-b.md]", issues[0].message);
+        assert_eq!("/adr/0001-a.md", issues[0].path);
+    }
+
+    #[test]
+    fn test_lint_staged_adr() {
+        let src = match TempDir::new("my_repo") {
+            Ok(src) => src,
+            Err(why) => {
+                println!("Unable to get src dir [{}]", why);
+                panic!("{}", why);
+            }
+        };
+        let repo = Repository::init(src.path()).unwrap();
+        let cfg = AdrToolConfig {
+            adr_src_dir: format!("{}", src.path().display()),
+            ..AdrToolConfig::default()
+        };
+
+        fs::write(
+            src.path().join("0002-b.adoc"),
+            "= b\n\n*Status:* {decided} *Date:* 2020-01-01\n",
+        )
+        .unwrap();
+        fs::write(
+            src.path().join("0003-c.adoc"),
+            "= c\n\n*Status:* {superseded} 0002-b.adoc *Date:* 2020-01-01\n",
+        )
+        .unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("0002-b.adoc")).unwrap();
+        index.add_path(Path::new("0003-c.adoc")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("John Smith", "john.smith@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "adrs", &tree, &[])
+            .unwrap();
+        assert!(lint_staged_adr(&cfg).unwrap().is_empty());
+
+        //git rm 0002-b.adoc, 0003 is unchanged
+        fs::remove_file(src.path().join("0002-b.adoc")).unwrap();
+        index.remove_path(Path::new("0002-b.adoc")).unwrap();
+        index.write().unwrap();
+        let issues = lint_staged_adr(&cfg).unwrap();
+        assert_eq!(1, issues.len());
+        assert_eq!("Broken reference [0002-b.adoc]", issues[0].message);
+        assert!(issues[0].path.ends_with("0003-c.adoc"));
+    }
+}
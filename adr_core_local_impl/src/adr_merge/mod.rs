@@ -0,0 +1,413 @@
+extern crate slog;
+use slog::*;
+
+use std::fs::{self};
+use std::io::{self};
+use std::path::Path;
+
+extern crate adr_config;
+
+use regex::Regex;
+
+use crate::adr_repo::*;
+
+fn get_logger() -> slog::Logger {
+    adr_config::logger::get_logger()
+}
+
+#[derive(Debug, Clone)]
+pub struct MergedAdr {
+    pub content: String,
+    /// The number of conflicts left in the content (with the usual conflict markers)
+    pub conflicts: usize,
+}
+
+/// The status line of an ADR, e.g. `*Status:* {superseded} 0015-new-decision.adoc  *Date:* 2020-01-01`, and the
+/// history and review comment lines recorded below it
+#[derive(Debug, Clone)]
+struct AdrHeader {
+    /// the index of the status line
+    position: usize,
+    /// the number of lines (the status line and the records)
+    len: usize,
+    before_status: String,
+    status: Status,
+    references: Vec<String>,
+    before_date: String,
+    date: String,
+    after_date: String,
+    records: Vec<String>,
+}
+
+impl AdrHeader {
+    fn parse(lines: &[&str]) -> Option<AdrHeader> {
+        lazy_static! {
+            static ref RE_STATUS_LINE: Regex = Regex::new(
+                r"^(.*\*Status:\*\s*)\{([^}]+)\}(.*?)(\s*\*Date:\*\s*)([0-9]{4}-[0-9]{2}-[0-9]{2})(.*)$"
+            )
+            .unwrap();
+        }
+
+        let position = lines.iter().position(|line| line.contains("*Status:*"))?;
+        let cap = RE_STATUS_LINE.captures(lines[position])?;
+        let records: Vec<String> = lines[position + 1..]
+            .iter()
+            .take_while(|line| {
+                let line = line.trim_start();
+                line.starts_with("// adr-history:") || line.starts_with("// adr-review:")
+            })
+            .map(|line| line.to_string())
+            .collect();
+
+        Some(AdrHeader {
+            position,
+            len: 1 + records.len(),
+            before_status: cap[1].to_string(),
+            status: Status::from_str(cap[2].to_string()),
+            references: cap[3].split_whitespace().map(String::from).collect(),
+            before_date: cap[4].to_string(),
+            date: cap[5].to_string(),
+            after_date: cap[6].to_string(),
+            records,
+        })
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let mut status_line = format!("{}{{{}}}", self.before_status, self.status.as_str());
+        for reference in self.references.iter() {
+            status_line.push_str(&format!(" {}", reference));
+        }
+        status_line.push_str(&format!(
+            "{}{}{}",
+            self.before_date, self.date, self.after_date
+        ));
+
+        let mut lines = vec![status_line];
+        lines.extend(self.records.iter().cloned());
+        lines
+    }
+}
+
+/// Picks the status of the merged ADR: the one which has changed if only one side has changed, the later along the
+/// lifecycle otherwise
+fn merge_status(base: Option<&Status>, ours: &Status, theirs: &Status) -> Status {
+    if Some(ours) == base || *ours == Status::NONE {
+        return *theirs;
+    }
+    if Some(theirs) == base || *theirs == Status::NONE {
+        return *ours;
+    }
+    match theirs.lifecycle_order() > ours.lifecycle_order() {
+        true => *theirs,
+        false => *ours,
+    }
+}
+
+/// Returns the date of a history or review comment line, e.g. `// adr-history: 2020-01-01 decided`
+fn record_date(record: &str) -> &str {
+    match record.find(": ") {
+        Some(position) => record[position + 2..]
+            .split_whitespace()
+            .next()
+            .unwrap_or(""),
+        None => "",
+    }
+}
+
+fn merge_headers(base: Option<&AdrHeader>, ours: &AdrHeader, theirs: &AdrHeader) -> AdrHeader {
+    let mut merged = ours.clone();
+    merged.status = merge_status(base.map(|base| &base.status), &ours.status, &theirs.status);
+    for reference in theirs.references.iter() {
+        if !merged.references.contains(reference) {
+            merged.references.push(reference.clone());
+        }
+    }
+    if theirs.date > merged.date {
+        merged.date = theirs.date.clone();
+    }
+    for record in theirs.records.iter() {
+        if !merged.records.contains(record) {
+            merged.records.push(record.clone());
+        }
+    }
+    //stable sort, so the records of the same day keep their order
+    merged
+        .records
+        .sort_by(|a, b| record_date(a).cmp(record_date(b)));
+
+    merged
+}
+
+/// Returns, for each line of `a`, the index of the matching line of `b` along their longest common subsequence
+fn match_lines(a: &[String], b: &[String]) -> Vec<Option<usize>> {
+    let mut lengths = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = match a[i] == b[j] {
+                true => lengths[i + 1][j + 1] + 1,
+                false => std::cmp::max(lengths[i + 1][j], lengths[i][j + 1]),
+            };
+        }
+    }
+
+    let mut matches = vec![None; a.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            matches[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    matches
+}
+
+/// Merges the lines which differ between the stable ones, with conflict markers if both sides have changed them
+fn merge_chunk(
+    base: &[String],
+    ours: &[String],
+    theirs: &[String],
+    merged: &mut Vec<String>,
+) -> usize {
+    if ours == theirs || theirs == base {
+        merged.extend(ours.iter().cloned());
+        0
+    } else if ours == base {
+        merged.extend(theirs.iter().cloned());
+        0
+    } else {
+        merged.push(String::from("<<<<<<< ours"));
+        merged.extend(ours.iter().cloned());
+        merged.push(String::from("======="));
+        merged.extend(theirs.iter().cloned());
+        merged.push(String::from(">>>>>>> theirs"));
+        1
+    }
+}
+
+/// A line based three-way merge (à la diff3). Returns the number of conflicts.
+fn merge_lines(
+    base: &[String],
+    ours: &[String],
+    theirs: &[String],
+    merged: &mut Vec<String>,
+) -> usize {
+    let ours_matches = match_lines(base, ours);
+    let theirs_matches = match_lines(base, theirs);
+
+    let mut conflicts = 0;
+    let (mut b, mut o, mut t) = (0, 0, 0);
+    loop {
+        //the next base line which is in the 3 versions
+        let stable = (b..base.len()).find_map(|i| match (ours_matches[i], theirs_matches[i]) {
+            (Some(oi), Some(ti)) => Some((i, oi, ti)),
+            _ => None,
+        });
+        match stable {
+            Some((i, oi, ti)) if i == b && oi == o && ti == t => {
+                merged.push(base[i].clone());
+                b += 1;
+                o += 1;
+                t += 1;
+            }
+            Some((i, oi, ti)) => {
+                conflicts += merge_chunk(&base[b..i], &ours[o..oi], &theirs[t..ti], merged);
+                b = i;
+                o = oi;
+                t = ti;
+            }
+            None => {
+                conflicts += merge_chunk(&base[b..], &ours[o..], &theirs[t..], merged);
+                return conflicts;
+            }
+        }
+    }
+}
+
+/// Merges the 3 versions of an ADR: the references of the status are merged, the later status along the lifecycle
+/// is kept, as well as the most recent date, and the history and review records of both sides are kept. The rest of
+/// the content is merged line by line, with the usual conflict markers when both sides have changed the same lines.
+///
+/// # Arguments
+///
+/// * `base` - the content of the common ancestor (empty if there is none)
+/// * `ours` - the content of the current branch
+/// * `theirs` - the content of the merged branch
+///
+pub fn merge_adr(base: &str, ours: &str, theirs: &str) -> MergedAdr {
+    let base_lines: Vec<&str> = base.lines().collect();
+    let ours_lines: Vec<&str> = ours.lines().collect();
+    let theirs_lines: Vec<&str> = theirs.lines().collect();
+
+    //once merged, the header is the same in the 3 versions so that only the rest of the content is merged line by line
+    let mut versions: Vec<Vec<String>> = vec![&base_lines, &ours_lines, &theirs_lines]
+        .into_iter()
+        .map(|lines| lines.iter().map(|line| line.to_string()).collect())
+        .collect();
+    let base_header = AdrHeader::parse(&base_lines);
+    match (
+        AdrHeader::parse(&ours_lines),
+        AdrHeader::parse(&theirs_lines),
+    ) {
+        (Some(ours_header), Some(theirs_header)) => {
+            let merged_header = merge_headers(base_header.as_ref(), &ours_header, &theirs_header);
+            debug!(
+                get_logger(),
+                "Merged status [{}] - [{}] to [{}]",
+                ours_header.status.as_str(),
+                theirs_header.status.as_str(),
+                merged_header.status.as_str()
+            );
+            for (lines, header) in versions.iter_mut().zip(vec![
+                base_header.as_ref(),
+                Some(&ours_header),
+                Some(&theirs_header),
+            ]) {
+                if let Some(header) = header {
+                    lines.splice(
+                        header.position..header.position + header.len,
+                        merged_header.lines(),
+                    );
+                }
+            }
+        }
+        _ => {
+            debug!(
+                get_logger(),
+                "Unable to find the status of both sides, merging line by line"
+            );
+        }
+    }
+
+    let mut merged = Vec::new();
+    let conflicts = merge_lines(&versions[0], &versions[1], &versions[2], &mut merged);
+    let mut content = merged.join("\n");
+    if ours.ends_with('\n') || theirs.ends_with('\n') {
+        content.push('\n');
+    }
+
+    MergedAdr { content, conflicts }
+}
+
+/// The git merge driver (cf. `adr init --git`): merges the 3 versions of an ADR into `ours`. Returns `false` if
+/// conflicts are left.
+///
+/// # Arguments
+///
+/// * `base` - the file of the common ancestor (`%O`)
+/// * `ours` - the file of the current branch (`%A`), where the result is written
+/// * `theirs` - the file of the merged branch (`%B`)
+///
+pub fn merge_adr_files(base: &Path, ours: &Path, theirs: &Path) -> io::Result<bool> {
+    let merged = merge_adr(
+        &fs::read_to_string(base)?,
+        &fs::read_to_string(ours)?,
+        &fs::read_to_string(theirs)?,
+    );
+    fs::write(ours, &merged.content)?;
+    info!(
+        get_logger(),
+        "Merged [{}] - [{}] conflict(s)",
+        ours.display(),
+        merged.conflicts
+    );
+
+    Ok(merged.conflicts == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adr_merge::*;
+
+    const BASE: &str = "= Use Kafka
+
+*Status:* {decided}  *Date:* 2020-01-01
+// adr-history: 2020-01-01 decided
+
+== Context
+
+Some context.
+
+== Decision
+
+We will use Kafka.
+";
+
+    #[test]
+    fn test_merge_adr_header() {
+        let ours = BASE
+            .replace(
+                "{decided}  *Date:* 2020-01-01",
+                "{superseded} 0005-e.adoc  *Date:* 2020-02-01",
+            )
+            .replace(
+                "// adr-history: 2020-01-01 decided",
+                "// adr-history: 2020-01-01 decided\n// adr-history: 2020-02-01 superseded",
+            );
+        let theirs = BASE
+            .replace(
+                "{decided}  *Date:* 2020-01-01",
+                "{completed} 0006-f.adoc  *Date:* 2020-01-15",
+            )
+            .replace(
+                "// adr-history: 2020-01-01 decided",
+                "// adr-history: 2020-01-01 decided\n// adr-history: 2020-01-15 completed",
+            )
+            .replace("Some context.", "Some more context.");
+
+        let merged = merge_adr(BASE, &ours, &theirs);
+        assert_eq!(0, merged.conflicts);
+        assert_eq!(
+            "= Use Kafka
+
+*Status:* {superseded} 0005-e.adoc 0006-f.adoc  *Date:* 2020-02-01
+// adr-history: 2020-01-01 decided
+// adr-history: 2020-01-15 completed
+// adr-history: 2020-02-01 superseded
+
+== Context
+
+Some more context.
+
+== Decision
+
+We will use Kafka.
+",
+            merged.content
+        );
+
+        //only one side has changed the status, even backward
+        let theirs = BASE.replace("{decided}", "{wip}");
+        let merged = merge_adr(BASE, BASE, &theirs);
+        assert_eq!(theirs, merged.content);
+    }
+
+    #[test]
+    fn test_merge_adr_conflict() {
+        let ours = BASE.replace("We will use Kafka.", "We will use Kafka 2.");
+        let theirs = BASE
+            .replace("We will use Kafka.", "We will use RabbitMQ.")
+            .replace("{decided}", "{completes}");
+
+        let merged = merge_adr(BASE, &ours, &theirs);
+        assert_eq!(1, merged.conflicts);
+        assert!(merged
+            .content
+            .contains("*Status:* {completes}  *Date:* 2020-01-01\n"));
+        assert!(merged.content.ends_with(
+            "== Decision
+
+<<<<<<< ours
+We will use Kafka 2.
+=======
+We will use RabbitMQ.
+>>>>>>> theirs
+"
+        ));
+    }
+}
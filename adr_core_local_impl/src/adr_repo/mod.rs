@@ -1,35 +1,28 @@
 extern crate slog;
-extern crate slog_term;
 use slog::*;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::{self};
 use std::io::{self};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 extern crate regex;
 use regex::Regex;
 
-use walkdir::{DirEntry, WalkDir};
+use walkdir::WalkDir;
+
+use rayon::prelude::*;
 
 extern crate adr_config;
-use adr_config::config::AdrToolConfig;
+use adr_config::config::{AdrToolConfig, TagDefinition};
+
+use crate::adr_cache::{get_cache_path, load_cache, store_cache, AdrCache};
+use crate::adr_discovery::AdrDiscovery;
 
 use chrono::prelude::*;
 
 fn get_logger() -> slog::Logger {
-    let cfg: AdrToolConfig = adr_config::config::get_config();
-
-    let decorator = slog_term::TermDecorator::new().build();
-    let drain = slog_term::FullFormat::new(decorator).build().fuse();
-    let drain = slog_async::Async::new(drain).build().fuse();
-    let drain = slog::LevelFilter::new(
-        drain,
-        Level::from_usize(cfg.log_level).unwrap_or(Level::Debug),
-    )
-    .fuse();
-
-    slog::Logger::root(drain, o!())
+    adr_config::logger::get_logger()
 }
 
 /// Creates the file (based on template file). Returns true if file is created, false if not (e.g. target file already exists...)
@@ -41,6 +34,30 @@ fn get_logger() -> slog::Logger {
 /// *
 ///
 pub fn create_adr(cfg: AdrToolConfig, path: Option<&str>, title: &str) -> io::Result<bool> {
+    create_adr_with_tags(cfg, path, title, &[]).map(|created| created.is_some())
+}
+
+/// Creates the file (based on template file) and tags it with `tags`, which replace the tags of the template.
+/// Returns the path of the new ADR (if created) or an `InvalidInput` error if one of the tags is not part of the controlled
+/// vocabulary (cf. `AdrToolConfig.allowed_tags`).
+///
+/// # Arguments
+///
+/// * `cfg` - The whole config object
+/// * `title`- the title of the ADR (specified by the user)
+/// * `tags` - the tags of the ADR. Aliases are replaced by the name of the tag
+///
+pub fn create_adr_with_tags(
+    cfg: AdrToolConfig,
+    path: Option<&str>,
+    title: &str,
+    tags: &[&str],
+) -> io::Result<Option<PathBuf>> {
+    let mut valid_tags = Vec::new();
+    for tag in tags {
+        valid_tags.push(check_tag(&cfg.allowed_tags, tag)?);
+    }
+
     let adr_template_dir = &cfg.adr_template_dir.as_str();
     let adr_template_file = &cfg.adr_template_file.as_str();
 
@@ -91,10 +108,25 @@ pub fn create_adr(cfg: AdrToolConfig, path: Option<&str>, title: &str) -> io::Re
             };
 
             new_adr.update_title(title);
+            //the title of the template (i.e. {%%ADR TITLE%%}) hides the status, so parse the ADR again
+            let mut new_adr = Adr::from(new_adr.base_path, new_adr.file_path, new_adr.content);
+            new_adr.update_date(Utc::now().date_naive());
+            new_adr.add_history(Utc::now().date_naive());
+            if !valid_tags.is_empty() {
+                for tag in new_adr.tags_array.clone() {
+                    new_adr.remove_tag(&tag);
+                }
+                for tag in valid_tags.iter() {
+                    new_adr.add_tag(tag, &cfg.allowed_tags)?;
+                }
+            }
 
             debug!(get_logger(), "Want to create ADR {:?}", &target_path);
             match fs::write(&target_path, new_adr.content) {
-                Ok(_val) => info!(get_logger(), "New ADR [{:?}] created", target_path),
+                Ok(_val) => {
+                    info!(get_logger(), "New ADR [{:?}] created", target_path);
+                    return Ok(Some(target_path));
+                }
                 Err(why) => {
                     error!(
                         get_logger(),
@@ -117,7 +149,7 @@ pub fn create_adr(cfg: AdrToolConfig, path: Option<&str>, title: &str) -> io::Re
         );
     }
 
-    Ok(!is_target_file)
+    Ok(None)
 }
 
 fn get_seq_id_from_name(name: &str) -> Result<usize> {
@@ -136,8 +168,12 @@ fn get_seq_id_from_name(name: &str) -> Result<usize> {
     Ok(id)
 }
 
+/// Returns the highest ID of the ADRs (0 if there is none)
 fn get_last_seq_id(adrs: Vec<Adr>) -> usize {
-    get_seq_id_from_name(&adrs[adrs.len() - 1].file_name).unwrap()
+    adrs.iter()
+        .map(|adr| get_seq_id_from_name(&adr.file_name).unwrap_or(0))
+        .max()
+        .unwrap_or(0)
 }
 
 fn sort_by_id(mut adrs: Vec<Adr>) -> Vec<Adr> {
@@ -148,7 +184,7 @@ fn sort_by_id(mut adrs: Vec<Adr>) -> Vec<Adr> {
 fn format_decision_name(cfg: AdrToolConfig, name: &str) -> Result<String> {
     let mut prefix = String::new();
     if cfg.use_id_prefix {
-        let adr_vec = list_all_adr(Path::new(cfg.adr_src_dir.as_str())).unwrap();
+        let adr_vec = scan_adr(Path::new(cfg.adr_src_dir.as_str())).unwrap();
         let last_seq_id = get_last_seq_id(adr_vec);
         prefix = format!("{:0>width$}-", last_seq_id + 1, width = cfg.id_prefix_width); //"{:0width$}", x, width = width
         debug!(get_logger(), "got seq number [{}]", prefix);
@@ -161,28 +197,9 @@ fn format_decision_name(cfg: AdrToolConfig, name: &str) -> Result<String> {
     Ok(name.to_string())
 }
 
-fn is_ok(entry: &DirEntry) -> bool {
-    let metadata = entry.metadata().unwrap();
-    let is_dir = metadata.is_dir();
-
-    let is_hidden = entry
-        .file_name()
-        .to_str()
-        .map(|s| s.starts_with("."))
-        .unwrap_or(false);
-
-    let is_adoc = entry
-        .file_name()
-        .to_str()
-        .map(|s| s.ends_with(".adoc"))
-        .unwrap_or(false);
-
-    (is_dir && !is_hidden) || (is_adoc && !is_hidden)
-}
-
 pub fn get_tags_popularity(base_path: &Path) -> Result<HashMap<String, u32>> {
     let mut popularity: HashMap<String, u32> = HashMap::new();
-    for adr in list_all_adr(base_path)? {
+    for adr in scan_adr(base_path)? {
         for tag in adr.tags_array.iter() {
             popularity
                 .entry(tag.to_string())
@@ -194,30 +211,392 @@ pub fn get_tags_popularity(base_path: &Path) -> Result<HashMap<String, u32>> {
     Ok(popularity)
 }
 
+/// Tags can be organized in hierarchies of `/`-separated segments (e.g. `security/authn`). Returns the tag with trimmed segments.
+///
+/// # Example
+///
+/// ```
+/// use adr_core::adr_repo::normalize_tag;
+///
+/// assert_eq!(normalize_tag(" security / authn "), "security/authn");
+/// assert_eq!(normalize_tag("security/"), "security");
+/// ```
+pub fn normalize_tag(tag: &str) -> String {
+    tag.split('/')
+        .map(|segment| segment.trim())
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<&str>>()
+        .join("/")
+}
+
+/// Returns true if `tag` is `filter` or one of its descendants.
+///
+/// # Example
+///
+/// ```
+/// use adr_core::adr_repo::tag_matches;
+///
+/// assert!(tag_matches("security/authn", "security"));
+/// assert!(tag_matches("security", "security"));
+/// assert!(!tag_matches("securityfoo", "security"));
+/// assert!(!tag_matches("security", "security/authn"));
+/// ```
+pub fn tag_matches(tag: &str, filter: &str) -> bool {
+    let tag = normalize_tag(tag);
+    let filter = normalize_tag(filter);
+
+    tag == filter || tag.starts_with(format!("{}/", filter).as_str())
+}
+
+/// Returns the tag and all its ancestors, from the root (e.g. `security`, `security/authn` for `security/authn`).
+pub fn tag_ancestors(tag: &str) -> Vec<String> {
+    let tag = normalize_tag(tag);
+    let segments: Vec<&str> = tag.split('/').filter(|s| !s.is_empty()).collect();
+
+    (1..=segments.len())
+        .map(|depth| segments[..depth].join("/"))
+        .collect()
+}
+
+/// Same as `get_tags_popularity` but each ADR is counted once for its tags and for all their ancestors,
+/// so that `security` is rolled up from `security/authn` and `security/crypto`. The map is sorted so that children follow their parent.
+pub fn get_tags_rollup(base_path: &Path) -> Result<BTreeMap<String, u32>> {
+    let mut rollup: BTreeMap<String, u32> = BTreeMap::new();
+    for adr in scan_adr(base_path)? {
+        let mut tags: Vec<String> = adr
+            .tags_array
+            .iter()
+            .flat_map(|tag| tag_ancestors(tag))
+            .collect();
+        tags.sort();
+        tags.dedup();
+
+        for tag in tags {
+            rollup.entry(tag).and_modify(|e| *e += 1).or_insert(1);
+        }
+    }
+
+    Ok(rollup)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TagValidation {
+    KNOWN,
+    ALIAS(String),
+    UNKNOWN(Option<String>),
+}
+
+/// Checks `tag` against the controlled vocabulary. Any tag is `KNOWN` if the vocabulary is empty.
+/// Unknown tags come with the closest allowed tag, if any is close enough.
+///
+/// # Example
+///
+/// ```
+/// use adr_config::config::TagDefinition;
+/// use adr_core::adr_repo::{validate_tag, TagValidation};
+///
+/// let vocabulary = vec![TagDefinition {
+///     name: String::from("security"),
+///     description: String::from("Authentication, authorization, crypto"),
+///     aliases: vec![String::from("sec")],
+/// }];
+/// assert_eq!(validate_tag(&vocabulary, "security"), TagValidation::KNOWN);
+/// assert_eq!(validate_tag(&vocabulary, "sec"), TagValidation::ALIAS(String::from("security")));
+/// assert_eq!(validate_tag(&vocabulary, "secuirty"), TagValidation::UNKNOWN(Some(String::from("security"))));
+/// ```
+pub fn validate_tag(vocabulary: &[TagDefinition], tag: &str) -> TagValidation {
+    let tag = tag.trim();
+    if vocabulary.is_empty() || vocabulary.iter().any(|def| def.name == tag) {
+        return TagValidation::KNOWN;
+    }
+
+    if let Some(def) = vocabulary
+        .iter()
+        .find(|def| def.aliases.iter().any(|alias| alias.trim() == tag))
+    {
+        return TagValidation::ALIAS(def.name.clone());
+    }
+
+    //suggest the closest name (or alias) of the vocabulary
+    let lowercase_tag = tag.to_lowercase();
+    let mut suggestion: Option<(usize, &str)> = None;
+    for def in vocabulary {
+        for candidate in std::iter::once(&def.name).chain(def.aliases.iter()) {
+            let distance = levenshtein(&lowercase_tag, &candidate.trim().to_lowercase());
+            let is_closer = match suggestion {
+                Some((best, _)) => distance < best,
+                None => true,
+            };
+            if is_closer {
+                suggestion = Some((distance, def.name.as_str()));
+            }
+        }
+    }
+
+    let max_distance = std::cmp::max(2, tag.chars().count() / 3);
+    TagValidation::UNKNOWN(
+        suggestion
+            .filter(|(distance, _)| *distance <= max_distance)
+            .map(|(_, name)| name.to_string()),
+    )
+}
+
+/// Returns the name of the tag in the controlled vocabulary (i.e. the tag itself, or the name of the tag it is an alias
+/// of), or an `InvalidInput` error if it is not part of the vocabulary (cf. `validate_tag`).
+pub fn check_tag(vocabulary: &[TagDefinition], tag: &str) -> io::Result<String> {
+    match validate_tag(vocabulary, tag) {
+        TagValidation::KNOWN => Ok(tag.trim().to_string()),
+        TagValidation::ALIAS(name) => {
+            info!(get_logger(), "Tag [{}] is an alias of [{}]", tag, name);
+            Ok(name)
+        }
+        TagValidation::UNKNOWN(suggestion) => {
+            let message = match suggestion {
+                Some(name) => format!("Unknown tag [{}] - did you mean [{}]?", tag, name),
+                None => format!("Unknown tag [{}]", tag),
+            };
+            error!(get_logger(), "{}", message);
+            Err(io::Error::new(io::ErrorKind::InvalidInput, message))
+        }
+    }
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == *cb { 0 } else { 1 };
+            current.push(std::cmp::min(
+                substitution,
+                std::cmp::min(previous[j + 1] + 1, current[j] + 1),
+            ));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Adds `tag` to the ADR `file_path`. Returns true if the ADR has been (or would be, if `dry_run`) modified, or an
+/// `InvalidInput` error if the tag is not part of the controlled vocabulary (cf. `check_tag`).
+///
+/// # Arguments
+///
+/// * `base_path` - The root directory where are all the ADRs. This is typically AdrToolConfig.adr_src_dir
+/// * `file_path` - The full path of the ADR to tag
+/// * `tag` - The tag to add
+/// * `vocabulary` - the controlled vocabulary, cf. `AdrToolConfig.allowed_tags`
+/// * `dry_run` - if true, the ADR is not written
+///
+pub fn add_tag(
+    base_path: &Path,
+    file_path: &str,
+    tag: &str,
+    vocabulary: &[TagDefinition],
+    dry_run: bool,
+) -> io::Result<bool> {
+    let mut adr = build_adr(base_path, Path::new(file_path))?;
+    let has_changed = adr.add_tag(tag, vocabulary)?;
+
+    write_tagged_adr(&adr, file_path, has_changed, dry_run)
+}
+
+/// Removes `tag` from the ADR `file_path`. Returns true if the ADR has been (or would be, if `dry_run`) modified.
+pub fn remove_tag(base_path: &Path, file_path: &str, tag: &str, dry_run: bool) -> io::Result<bool> {
+    let mut adr = build_adr(base_path, Path::new(file_path))?;
+    let has_changed = adr.remove_tag(tag);
+
+    write_tagged_adr(&adr, file_path, has_changed, dry_run)
+}
+
+/// Renames the tag `old` to `new` across all the ADRs of `base_path`. Returns the paths of the modified (or to be modified, if `dry_run`) ADRs.
+pub fn rename_tag(
+    base_path: &Path,
+    old: &str,
+    new: &str,
+    vocabulary: &[TagDefinition],
+    dry_run: bool,
+) -> io::Result<Vec<String>> {
+    merge_tags(base_path, &[old], new, vocabulary, dry_run)
+}
+
+/// Merges all the `tags` into `into` across all the ADRs of `base_path`. Returns the paths of the modified (or to be modified, if `dry_run`) ADRs,
+/// or an `InvalidInput` error (and no ADR is modified) if `into` is not part of the controlled vocabulary `vocabulary` (cf. `check_tag`).
+///
+/// # Example
+///
+/// `merge_tags(base_path, &["Security", "sec"], "security", &[], false)` will retag all the ADRs tagged with `Security` or `sec` with `security`.
+///
+pub fn merge_tags(
+    base_path: &Path,
+    tags: &[&str],
+    into: &str,
+    vocabulary: &[TagDefinition],
+    dry_run: bool,
+) -> io::Result<Vec<String>> {
+    let into = check_tag(vocabulary, into)?;
+    let mut changed_files = Vec::new();
+    for mut adr in list_all_adr(base_path)? {
+        let mut has_changed = false;
+        for tag in tags {
+            has_changed |= adr.replace_tag(tag, &into, vocabulary)?;
+        }
+
+        let path = adr.path();
+        if write_tagged_adr(&adr, &path, has_changed, dry_run)? {
+            changed_files.push(path);
+        }
+    }
+
+    Ok(changed_files)
+}
+
+fn write_tagged_adr(adr: &Adr, path: &str, has_changed: bool, dry_run: bool) -> io::Result<bool> {
+    if !has_changed {
+        debug!(get_logger(), "Tags of ADR [{}] are left unchanged", path);
+        return Ok(false);
+    }
+
+    if dry_run {
+        info!(
+            get_logger(),
+            "[dry-run] ADR [{}] would be tagged with [{}]", path, adr.tags
+        );
+    } else {
+        fs::write(path, &adr.content)?;
+        info!(
+            get_logger(),
+            "ADR [{}] is now tagged with [{}]", path, adr.tags
+        );
+    }
+
+    Ok(true)
+}
+
+/// Lists the ADRs of the directory, sorted by ID. Only the files which have changed since the last call are parsed
+/// again, when the directory has a cache (cf. `adr_cache::get_cache_path`).
 pub fn list_all_adr(dir: &Path) -> io::Result<Vec<Adr>> {
-    let mut results = std::vec::Vec::new();
+    let cache_path = get_cache_path(&adr_config::config::get_config(), dir);
+    list_all_adr_with_cache(dir, cache_path.as_deref())
+}
+
+/// Same as `list_all_adr` but the ADRs are returned without their content, which is read when it is needed (cf.
+/// `Adr::load_content` and `Adr::references`), so that the content of large decision logs is not held in memory.
+/// Returns a Vec, as all the ADRs are parsed (and cached) before being returned.
+pub fn scan_adr(dir: &Path) -> io::Result<Vec<Adr>> {
+    let cache_path = get_cache_path(&adr_config::config::get_config(), dir);
+    scan_adr_with_cache(dir, cache_path.as_deref(), false)
+}
+
+/// Lists the ADRs of the directory, sorted by ID, with the metadata of the files which have not changed (same
+/// modification time and size) taken from the cache, if any.
+///
+/// # Arguments
+///
+/// * `dir` - the directory of the ADRs
+/// * `cache_path` - the cache of the metadata of the ADRs of `dir`, updated with the changed files
+///
+pub fn list_all_adr_with_cache(dir: &Path, cache_path: Option<&Path>) -> io::Result<Vec<Adr>> {
+    scan_adr_with_cache(dir, cache_path, true)
+}
+
+/// Parses the ADRs of the directory in parallel (one file per task), sorted by ID
+///
+/// # Arguments
+///
+/// * `dir` - the directory of the ADRs
+/// * `cache_path` - the cache of the metadata of the ADRs of `dir`, updated with the changed files
+/// * `with_content` - whether the ADRs keep their content, or are only their metadata
+///
+fn scan_adr_with_cache(
+    dir: &Path,
+    cache_path: Option<&Path>,
+    with_content: bool,
+) -> io::Result<Vec<Adr>> {
+    let mut cache = match cache_path {
+        Some(path) => load_cache(path, dir),
+        None => AdrCache::new(dir),
+    };
+    let files = list_adr_files(dir)?;
+
+    //the cache is only read while the files are parsed, the parsed files are cached afterwards
+    let scanned: Vec<(Adr, Option<&fs::Metadata>)> = files
+        .par_iter()
+        .filter_map(|(path, metadata)| {
+            let scanned = match cache.get(dir, path, metadata) {
+                Some(mut adr) => match with_content {
+                    true => match adr.load_content() {
+                        Ok(_) => Ok((adr, None)),
+                        Err(why) => Err(why),
+                    },
+                    false => Ok((adr, None)),
+                },
+                //the content of a parsed file is dropped right away, the cache only keeps the metadata
+                None => build_adr(dir, path).map(|mut adr| {
+                    if !with_content {
+                        adr.content = String::new();
+                    }
+                    (adr, Some(metadata))
+                }),
+            };
+            match scanned {
+                Ok(scanned) => Some(scanned),
+                Err(_why) => {
+                    debug!(get_logger(), "Unable to read file [{:?}]", path);
+                    None
+                }
+            }
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(scanned.len());
+    let mut has_changed = false;
+    for (adr, parsed) in scanned {
+        if let Some(metadata) = parsed {
+            cache.insert(&adr, metadata);
+            has_changed = true;
+        }
+        results.push(adr);
+    }
+
+    //the files which have been removed
+    has_changed |= cache.retain(&results);
+    if let (Some(path), true) = (cache_path, has_changed) {
+        //best effort, the cache is rebuilt if it cannot be written
+        if let Err(why) = store_cache(path, &cache) {
+            debug!(
+                get_logger(),
+                "Unable to write cache [{:?}] - [{}]", path, why
+            );
+        }
+    }
+
+    results = sort_by_id(results);
+
+    Ok(results)
+}
+
+/// Returns the ADR files of the directory (cf. `adr_discovery::AdrDiscovery`), with their metadata
+fn list_adr_files(dir: &Path) -> io::Result<Vec<(PathBuf, fs::Metadata)>> {
+    let mut results = Vec::new();
 
     if dir.is_dir() {
+        let discovery = AdrDiscovery::new(&adr_config::config::get_config(), dir);
         let walker = WalkDir::new(dir).follow_links(true).into_iter();
-        for entry in walker.filter_entry(|e| is_ok(e)) {
+        for entry in walker
+            .filter_entry(|e| e.depth() == 0 || discovery.is_adr(e.path(), e.file_type().is_dir()))
+        {
             let entry = entry?;
             debug!(get_logger(), "got file [{:?}]", entry.path());
             let metadata = entry.metadata().unwrap();
             if metadata.is_file() {
-                match build_adr(dir, entry.path()) {
-                    Ok(adr) => {
-                        results.push(adr);
-                    }
-                    Err(_why) => {
-                        debug!(get_logger(), "Unable to read file [{:?}]", entry.path());
-                    }
-                };
+                results.push((entry.into_path(), metadata));
             }
         }
     }
 
-    results = sort_by_id(results);
-
     Ok(results)
 }
 
@@ -284,8 +663,148 @@ pub fn build_adr(base_path: &Path, full_path: &Path) -> io::Result<Adr> {
     Ok(adr)
 }
 
+/// Transitions the ADR to decided, whatever its reviews, cf. `transition_to_decided_with_quorum`.
 pub fn transition_to_decided(base_path: &Path, file_path: &str) -> io::Result<bool> {
-    transition_to(TransitionStatus::DECIDED, base_path, file_path, "")
+    transition_to_decided_with_quorum(base_path, file_path, 0)
+}
+
+/// Transitions the ADR to decided, if it has been approved by at least `quorum` reviewers (cf. `approve_review`).
+/// When `quorum` is `0`, reviews are optional and it is the same as `transition_to_decided`.
+pub fn transition_to_decided_with_quorum(
+    base_path: &Path,
+    file_path: &str,
+    quorum: usize,
+) -> io::Result<bool> {
+    transition_to(TransitionStatus::DECIDED, base_path, file_path, "", quorum)
+}
+
+/// Returns true if the ADR can be decided, i.e. it is in review and has been approved by at least `quorum`
+/// reviewers, or `quorum` is `0`. The missing approvals are logged.
+fn has_quorum(adr: &Adr, quorum: usize) -> bool {
+    if quorum == 0 {
+        return true;
+    }
+    let approvals = adr.approvals();
+    if adr.status != Status::REVIEW || approvals.len() < quorum {
+        error!(
+            get_logger(),
+            "ADR [{}] cannot be decided - status is [{}] and it needs [{}] approval(s) - got [{}]",
+            adr.path(),
+            adr.status.as_str(),
+            quorum,
+            approvals.len()
+        );
+        return false;
+    }
+
+    true
+}
+
+/// Transitions the wip ADR to review and records the requested reviewers (if any).
+pub fn request_review(base_path: &Path, file_path: &str, reviewers: &[&str]) -> io::Result<bool> {
+    let mut adr = build_adr(base_path, Path::new(file_path))?;
+    if !adr.update_status(TransitionStatus::REVIEW) {
+        error!(
+            get_logger(),
+            "ADR [{}] cannot be reviewed - status is [{}]",
+            file_path,
+            adr.status.as_str()
+        );
+        return Ok(false);
+    }
+    for reviewer in reviewers.iter() {
+        adr.add_review(
+            Utc::now().date_naive(),
+            ReviewAction::REQUESTED,
+            reviewer,
+            "",
+        );
+    }
+
+    write_reviewed_adr(&adr, file_path)
+}
+
+/// Records the approval of the ADR (which must be in review) by the given reviewer, e.g. `@alice`, whose review must
+/// have been requested (cf. `request_review`).
+pub fn approve_review(base_path: &Path, file_path: &str, reviewer: &str) -> io::Result<bool> {
+    let mut adr = build_adr(base_path, Path::new(file_path))?;
+    if adr.status != Status::REVIEW {
+        error!(
+            get_logger(),
+            "ADR [{}] cannot be approved - status is [{}]",
+            file_path,
+            adr.status.as_str()
+        );
+        return Ok(false);
+    }
+    if !adr
+        .requested_reviewers()
+        .iter()
+        .any(|requested| is_same_reviewer(requested, reviewer))
+    {
+        error!(
+            get_logger(),
+            "ADR [{}] cannot be approved by [{}] - the review has not been requested",
+            file_path,
+            reviewer
+        );
+        return Ok(false);
+    }
+    adr.add_review(
+        Utc::now().date_naive(),
+        ReviewAction::APPROVED,
+        reviewer,
+        "",
+    );
+
+    write_reviewed_adr(&adr, file_path)
+}
+
+/// The reviewers are compared without their `@` and case-insensitively, e.g. `alice` is `@Alice`
+fn is_same_reviewer(a: &str, b: &str) -> bool {
+    let normalize = |reviewer: &str| reviewer.trim().trim_start_matches('@').to_lowercase();
+    normalize(a) == normalize(b)
+}
+
+/// Records the rejection of the ADR (which must be in review) by the given reviewer and sends it back to wip.
+pub fn reject_review(
+    base_path: &Path,
+    file_path: &str,
+    reviewer: &str,
+    note: &str,
+) -> io::Result<bool> {
+    let mut adr = build_adr(base_path, Path::new(file_path))?;
+    if adr.status != Status::REVIEW {
+        error!(
+            get_logger(),
+            "ADR [{}] cannot be rejected - status is [{}]",
+            file_path,
+            adr.status.as_str()
+        );
+        return Ok(false);
+    }
+    adr.add_review(
+        Utc::now().date_naive(),
+        ReviewAction::REJECTED,
+        reviewer,
+        note,
+    );
+    adr.update_status(TransitionStatus::REJECTED);
+
+    write_reviewed_adr(&adr, file_path)
+}
+
+fn write_reviewed_adr(adr: &Adr, path: &str) -> io::Result<bool> {
+    fs::write(path, &adr.content)?;
+    info!(
+        get_logger(),
+        "ADR [{}] is [{}] - approved by [{}]",
+        path,
+        adr.status.as_str(),
+        adr.approvals().join(", ")
+    );
+
+    Ok(true)
 }
 
 pub fn transition_to_superseded_by(
@@ -293,24 +812,52 @@ pub fn transition_to_superseded_by(
     file_path: &str,
     by: &str,
 ) -> io::Result<bool> {
-    transition_to(TransitionStatus::SUPERSEDED, base_path, file_path, by)
+    transition_to(TransitionStatus::SUPERSEDED, base_path, file_path, by, 0)
 }
 
 pub fn transition_to_completed_by(base_path: &Path, file_path: &str, by: &str) -> io::Result<bool> {
-    transition_to(TransitionStatus::COMPLETED, base_path, file_path, by)
+    transition_to(TransitionStatus::COMPLETED, base_path, file_path, by, 0)
 }
 
 pub fn transition_to_obsoleted(base_path: &Path, file_path: &str) -> io::Result<bool> {
-    transition_to(TransitionStatus::CANCELLED, base_path, file_path, "")
+    transition_to(TransitionStatus::CANCELLED, base_path, file_path, "", 0)
 }
 
+/// Transitions the ADR `from_path` by the ADR `by_path` of the same directory, cf. `transition_across`.
 pub fn transition_to(
     transition: TransitionStatus,
     base_path: &Path,
     from_path: &str,
     by_path: &str,
+    quorum: usize,
+) -> io::Result<bool> {
+    transition_across(
+        transition, base_path, from_path, base_path, by_path, None, quorum,
+    )
+}
+
+/// Transitions the ADR `from_path` by the ADR `by_path`, which may belong to another workspace (cf. `adr_federation`).
+///
+/// # Arguments
+///
+/// * `transition` - the transition of `from_path`, the reverse one being applied to `by_path`
+/// * `from_base_path` - the directory of the ADRs of `from_path`
+/// * `from_path` - the path of the transitioned ADR
+/// * `by_base_path` - the directory of the ADRs of `by_path`
+/// * `by_path` - the path of the ADR it is transitioned by (none if empty)
+/// * `references` - the references written in `by_path` and in `from_path` (e.g. `platform:0012`), their file names if none
+/// * `quorum` - the approvals needed to transition to decided (cf. `AdrToolConfig.review_quorum`), none if `0`
+///
+pub fn transition_across(
+    transition: TransitionStatus,
+    from_base_path: &Path,
+    from_path: &str,
+    by_base_path: &Path,
+    by_path: &str,
+    references: Option<(&str, &str)>,
+    quorum: usize,
 ) -> io::Result<bool> {
-    let mut from_adr = match build_adr(base_path, Path::new(from_path)) {
+    let mut from_adr = match build_adr(from_base_path, Path::new(from_path)) {
         Ok(adr) => adr,
         Err(why) => {
             error!(
@@ -322,6 +869,10 @@ pub fn transition_to(
     };
     let from_old_status = from_adr.status.as_str();
 
+    if transition == TransitionStatus::DECIDED && !has_quorum(&from_adr, quorum) {
+        return Ok(false);
+    }
+
     //if transition has been declined, we can stop here
     match from_adr.update_status(transition) {
         true => {
@@ -349,13 +900,19 @@ pub fn transition_to(
             match by_path.is_empty() {
                 true => transition_adr(&from_adr, from_path, from_old_status),
                 false => {
-                    let mut by_adr = build_adr(base_path, Path::new(by_path))?;
+                    let mut by_adr = build_adr(by_base_path, Path::new(by_path))?;
                     let by_old_status = by_adr.status.as_str();
                     //if transition has been declined, we can stop here
                     match by_adr.update_status(TransitionStatus::revert(transition)) {
                         true => {
-                            from_adr.add_reference(format!("{}", by_adr.file_name).as_str());
-                            by_adr.add_reference(format!("{}", from_adr.file_name).as_str());
+                            let (from_reference, by_reference) = match references {
+                                Some((from_reference, by_reference)) => {
+                                    (String::from(from_reference), String::from(by_reference))
+                                }
+                                None => (from_adr.file_name.clone(), by_adr.file_name.clone()),
+                            };
+                            from_adr.add_reference(by_reference.as_str());
+                            by_adr.add_reference(from_reference.as_str());
                             Ok(transition_adr(&from_adr, from_path, from_old_status)?
                                 == transition_adr(&by_adr, by_path, by_old_status)?)
                         }
@@ -377,13 +934,15 @@ pub fn transition_to(
     }
 }
 
-#[derive(Debug, Default)]
+/// A Decision Record. Its parsed metadata is serializable, cf. `adr_cache`
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Adr {
     //pub path: String, //the path from config.adr_root_dir (which is user dependant)
     pub file_id: usize,
     pub file_name: String,
     pub file_path: String,
     pub base_path: String,
+    #[serde(skip)]
     pub content: String,
     pub title: String,
     pub date: String,
@@ -391,6 +950,71 @@ pub struct Adr {
     pub state: AdrState,
     pub tags: String,
     pub tags_array: Vec<String>,
+    pub deciders: Vec<String>,
+    pub history: Vec<AdrTransition>,
+    pub revisit: Option<String>,
+    pub reviews: Vec<AdrReview>,
+    #[serde(skip)]
+    pub git_history: Option<AdrGitHistory>,
+    /// The workspace of the ADR when listed across the workspaces, cf. `adr_federation::list_all_federated_adr`
+    #[serde(skip)]
+    pub workspace: String,
+}
+
+/// A transition recorded in the ADR, cf. `Adr::get_history`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdrTransition {
+    pub date: String,
+    pub status: Status,
+}
+
+/// The dates and authors of an ADR taken from the git history of its file, cf. `adr_git::load_git_history`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AdrGitHistory {
+    pub created_at: String,
+    pub last_modified: String,
+    /// The date of the first commit with each status
+    pub status_dates: Vec<AdrTransition>,
+    pub authors: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
+pub enum ReviewAction {
+    REQUESTED,
+    APPROVED,
+    REJECTED,
+}
+
+impl ReviewAction {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ReviewAction::REQUESTED => "requested",
+            ReviewAction::APPROVED => "approved",
+            ReviewAction::REJECTED => "rejected",
+        }
+    }
+}
+
+impl std::str::FromStr for ReviewAction {
+    type Err = String;
+
+    fn from_str(val: &str) -> std::result::Result<ReviewAction, String> {
+        match val {
+            "requested" => Ok(ReviewAction::REQUESTED),
+            "approved" => Ok(ReviewAction::APPROVED),
+            "rejected" => Ok(ReviewAction::REJECTED),
+            _ => Err(format!("Unknown review action [{}]", val)),
+        }
+    }
+}
+
+/// A review recorded in the ADR, cf. `Adr::get_reviews`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdrReview {
+    pub date: String,
+    pub action: ReviewAction,
+    pub reviewer: String,
+    pub note: String,
 }
 
 impl Adr {
@@ -407,6 +1031,12 @@ impl Adr {
             state: AdrState::default(),
             tags: String::new(),
             tags_array: Vec::new(),
+            deciders: Vec::new(),
+            history: Vec::new(),
+            revisit: None,
+            reviews: Vec::new(),
+            git_history: None,
+            workspace: String::new(),
         }
     }
 
@@ -417,6 +1047,8 @@ impl Adr {
             static ref RE_TITLE: Regex = Regex::new(r"= (.+)").unwrap();
             static ref RE_STATUS: Regex = Regex::new(r"\{(.+)\}").unwrap();
             static ref RE_DATE: Regex = Regex::new(r"([0-9]{4}-[0-9]{2}-[0-9]{2})").unwrap();
+            static ref RE_STATUS_DATE: Regex =
+                Regex::new(r"\*Date:\*\s*([0-9]{4}-[0-9]{2}-[0-9]{2})").unwrap();
         }
 
         //set file/path properties
@@ -448,8 +1080,11 @@ impl Adr {
             }
         };
 
-        //set date
-        adr.date = match RE_DATE.captures(&adr.content) {
+        //set date (the one of the status line, header attributes like :revisit: come first)
+        adr.date = match RE_STATUS_DATE
+            .captures(&adr.content)
+            .or_else(|| RE_DATE.captures(&adr.content))
+        {
             Some(val) => val[1].trim().to_string(),
             None => {
                 debug!(
@@ -467,6 +1102,12 @@ impl Adr {
         adr.tags = tags.0;
         adr.tags_array = tags.1;
 
+        //set deciders/history
+        adr.deciders = Adr::get_deciders(&adr.content);
+        adr.history = Adr::get_history(&adr.content);
+        adr.revisit = Adr::get_revisit(&adr.content);
+        adr.reviews = Adr::get_reviews(&adr.content);
+
         //set status/state
         adr.status = Status::from_str(match RE_STATUS.captures(&adr.content) {
             Some(val) => val[1].trim().to_string(),
@@ -486,11 +1127,28 @@ impl Adr {
         adr
     }
 
+    /// Returns the ID of the ADR, qualified by its workspace if any (e.g. `platform:0012`)
+    pub fn qualified_id(&self) -> String {
+        match self.workspace.is_empty() {
+            true => self.file_id.to_string(),
+            false => format!("{}:{:04}", self.workspace, self.file_id),
+        }
+    }
+
     pub fn path(&self) -> String {
         let full_path = Path::new(self.base_path.as_str()).join(self.file_path.as_str());
         return format!("{}", full_path.display());
     }
 
+    /// Returns the content of the ADR, read from its file if it has been listed without it (cf. `scan_adr`)
+    pub fn load_content(&mut self) -> io::Result<&str> {
+        if self.content.is_empty() {
+            self.content = fs::read_to_string(self.path())?;
+        }
+
+        Ok(&self.content)
+    }
+
     pub fn get_tags(val: &String) -> (String, Vec<String>) {
         lazy_static! {
             static ref RE_TAGS: Regex = Regex::new(r"(\[tags]\#([^#]+)\#)").unwrap();
@@ -499,7 +1157,7 @@ impl Adr {
         let mut tags_str = String::from("");
         for cap in RE_TAGS.captures_iter(val) {
             use std::fmt::Write;
-            write!(tags_str, "#{} ", &cap[2]).unwrap();
+            write!(tags_str, "#{} ", normalize_tag(&cap[2])).unwrap();
         }
 
         let tags = tags_str
@@ -511,6 +1169,34 @@ impl Adr {
         (tags_str, tags)
     }
 
+    /// Returns the people of the `_Decision Makers_` row of the ADR header, e.g. `|_Decision Makers_ |@John Smith, @Jane Doe`
+    pub fn get_deciders(val: &str) -> Vec<String> {
+        lazy_static! {
+            static ref RE_DECIDERS: Regex =
+                Regex::new(r"(?m)^\s*\|\s*_?Decision Makers_?\s*\|(.*)$").unwrap();
+        }
+
+        match RE_DECIDERS.captures(val) {
+            Some(cap) => cap[1]
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the revisit date of the ADR, i.e. the `:revisit: 2020-10-28` (or `:review-by: 2020-10-28`) header attribute
+    pub fn get_revisit(val: &str) -> Option<String> {
+        lazy_static! {
+            static ref RE_REVISIT: Regex =
+                Regex::new(r"(?m)^:(?:revisit|review-by):\s*([0-9]{4}-[0-9]{2}-[0-9]{2})\s*$")
+                    .unwrap();
+        }
+
+        RE_REVISIT.captures(val).map(|cap| cap[1].to_string())
+    }
+
     pub fn update_status(&mut self, transition: TransitionStatus) -> bool {
         let current_status = format!("{{{status}}}", status = self.status.as_str()); //you escape { with a { and final status is {wip}  o_O
         let mut state = self.state;
@@ -527,7 +1213,8 @@ impl Adr {
                 .replace(current_status.as_str(), new_status.as_str());
             self.status = state.status;
             self.state = state;
-            self.update_date(Utc::today());
+            self.update_date(Utc::now().date_naive());
+            self.add_history(Utc::now().date_naive());
             has_been_modified
         } else {
             debug!(get_logger(), "Transition has been declined");
@@ -535,6 +1222,193 @@ impl Adr {
         }
     }
 
+    /// Returns the transitions recorded in the ADR, i.e. the `// adr-history: 2019-10-28 decided` comment lines (which are not rendered by asciidoctor).
+    pub fn get_history(val: &str) -> Vec<AdrTransition> {
+        lazy_static! {
+            static ref RE_HISTORY: Regex =
+                Regex::new(r"(?m)^\s*// adr-history: ([0-9]{4}-[0-9]{2}-[0-9]{2}) (\S+)\s*$")
+                    .unwrap();
+        }
+
+        RE_HISTORY
+            .captures_iter(val)
+            .map(|cap| AdrTransition {
+                date: cap[1].to_string(),
+                status: Status::from_str(cap[2].to_string()),
+            })
+            .collect()
+    }
+
+    /// Records the current status of the ADR (at `today`) in its history, just below the status line (or the last recorded transition or review).
+    pub fn add_history(&mut self, today: NaiveDate) {
+        let transition = AdrTransition {
+            date: today.format("%Y-%m-%d").to_string(),
+            status: self.status,
+        };
+        debug!(get_logger(), "Want to add [{:?}] to history", transition);
+
+        let line = format!(
+            "// adr-history: {} {}",
+            transition.date,
+            transition.status.as_str()
+        );
+        if self.insert_record(&line) {
+            self.history.push(transition);
+        }
+    }
+
+    /// Returns the date at which the ADR got the given status for the first time, from its recorded history
+    /// (cf. `get_history`) or else from its git history
+    pub fn status_date(&self, status: Status) -> Option<String> {
+        let recorded = self
+            .history
+            .iter()
+            .find(|transition| transition.status == status);
+        let committed = || {
+            self.git_history.as_ref().and_then(|git_history| {
+                git_history
+                    .status_dates
+                    .iter()
+                    .find(|transition| transition.status == status)
+            })
+        };
+        recorded
+            .or_else(committed)
+            .map(|transition| transition.date.clone())
+    }
+
+    /// Returns the creation date of the ADR, from its git history or else from its recorded history
+    pub fn created_at(&self) -> Option<String> {
+        match &self.git_history {
+            Some(git_history) => Some(git_history.created_at.clone()),
+            None => self
+                .history
+                .first()
+                .map(|transition| transition.date.clone()),
+        }
+    }
+
+    /// Returns the reviews recorded in the ADR, i.e. the `// adr-review: 2019-10-28 approved @alice` comment lines.
+    /// A rejection comes with the note of the reviewer, e.g. `// adr-review: 2019-10-28 rejected @alice: too expensive`
+    pub fn get_reviews(val: &str) -> Vec<AdrReview> {
+        lazy_static! {
+            static ref RE_REVIEW: Regex = Regex::new(
+                r"(?m)^\s*// adr-review: ([0-9]{4}-[0-9]{2}-[0-9]{2}) (requested|approved|rejected) ([^:\n]+?)(?::[ \t]*(.*?))?[ \t]*$"
+            )
+            .unwrap();
+        }
+
+        RE_REVIEW
+            .captures_iter(val)
+            .map(|cap| AdrReview {
+                date: cap[1].to_string(),
+                action: cap[2].parse::<ReviewAction>().unwrap(),
+                reviewer: cap[3].trim().to_string(),
+                note: cap.get(4).map_or("", |m| m.as_str()).to_string(),
+            })
+            .collect()
+    }
+
+    /// Records a review of the ADR (at `today`), just below the status line (or the last recorded transition or review).
+    pub fn add_review(
+        &mut self,
+        today: NaiveDate,
+        action: ReviewAction,
+        reviewer: &str,
+        note: &str,
+    ) {
+        let reviewer = match reviewer.trim() {
+            reviewer if reviewer.starts_with('@') => reviewer.to_string(),
+            reviewer => format!("@{}", reviewer),
+        };
+        let review = AdrReview {
+            date: today.format("%Y-%m-%d").to_string(),
+            action,
+            reviewer,
+            note: note.trim().replace('\n', " "),
+        };
+        debug!(get_logger(), "Want to add review [{:?}]", review);
+
+        let mut line = format!(
+            "// adr-review: {} {} {}",
+            review.date,
+            review.action.as_str(),
+            review.reviewer
+        );
+        if !review.note.is_empty() {
+            line = format!("{}: {}", line, review.note);
+        }
+        if self.insert_record(&line) {
+            self.reviews.push(review);
+        }
+    }
+
+    /// Returns the distinct reviewers who approved the ADR since its last rejection, among the requested ones
+    pub fn approvals(&self) -> Vec<String> {
+        let requested = self.requested_reviewers();
+        let mut approvals: Vec<String> = Vec::new();
+        for review in self.reviews.iter() {
+            let is_counted = |reviewers: &[String]| {
+                reviewers
+                    .iter()
+                    .any(|reviewer| is_same_reviewer(reviewer, &review.reviewer))
+            };
+            match review.action {
+                ReviewAction::REJECTED => approvals.clear(),
+                ReviewAction::APPROVED if is_counted(&requested) && !is_counted(&approvals) => {
+                    approvals.push(review.reviewer.clone())
+                }
+                _ => (),
+            }
+        }
+
+        approvals
+    }
+
+    /// Returns the reviewers whose review has been requested
+    pub fn requested_reviewers(&self) -> Vec<String> {
+        self.reviews
+            .iter()
+            .filter(|review| review.action == ReviewAction::REQUESTED)
+            .map(|review| review.reviewer.clone())
+            .collect()
+    }
+
+    /// Inserts the comment line below the status line and the already recorded transitions and reviews
+    fn insert_record(&mut self, line: &str) -> bool {
+        let current_status = format!("{{{status}}}", status = self.status.as_str());
+        let position = match self.content.find(current_status.as_str()) {
+            Some(position) => position,
+            None => {
+                debug!(
+                    get_logger(),
+                    "Unable to find status [{}] to record [{}]", current_status, line
+                );
+                return false;
+            }
+        };
+        let line_end = |from: usize| -> usize {
+            match self.content[from..].find('\n') {
+                Some(offset) => from + offset,
+                None => self.content.len(),
+            }
+        };
+        let mut position = line_end(position);
+        while position < self.content.len() {
+            let next_line_end = line_end(position + 1);
+            let next_line = self.content[position + 1..next_line_end].trim_start();
+            if !next_line.starts_with("// adr-history:") && !next_line.starts_with("// adr-review:")
+            {
+                break;
+            }
+            position = next_line_end;
+        }
+
+        self.content
+            .insert_str(position, format!("\n{}", line).as_str());
+        true
+    }
+
     pub fn add_reference(&mut self, adr_title: &str) {
         let current_status = format!("{{{status}}}", status = self.status.as_str()); //you escape { with a { and final status is {wip}  o_O
         let new_status = format!(
@@ -554,25 +1428,157 @@ impl Adr {
             .replace(current_status.as_str(), new_status.as_str());
     }
 
-    pub fn update_date(&mut self, today: Date<Utc>) {
-        let new_date = today.format("%Y-%m-%d").to_string();
-        debug!(get_logger(), "Want to update ADR to date [{}]", new_date);
+    /// Returns the references written after the status (cf. `add_reference`), e.g. `0015-new-decision.adoc` for
+    /// `*Status:* {superseded} 0015-new-decision.adoc *Date:* ...`. The content of an ADR listed without it (cf.
+    /// `scan_adr`) is read from its file.
+    pub fn references(&self) -> Vec<String> {
+        lazy_static! {
+            static ref RE_REFERENCES: Regex =
+                Regex::new(r"\*Status:\*\s*\{[^}]+\}(.*?)\*Date:\*").unwrap();
+        }
+
+        let loaded_content;
+        let content = match self.content.is_empty() {
+            true => {
+                loaded_content = fs::read_to_string(self.path()).unwrap_or_default();
+                &loaded_content
+            }
+            false => &self.content,
+        };
+        match RE_REFERENCES.captures(content) {
+            Some(cap) => cap[1].split_whitespace().map(String::from).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn update_date(&mut self, today: NaiveDate) {
+        let new_date = today.format("%Y-%m-%d").to_string();
+        debug!(get_logger(), "Want to update ADR to date [{}]", new_date);
+
+        self.date = new_date;
+        let re_status_date = Regex::new(r"(\*Date:\*\s*)(\d{4})-(\d{2})-(\d{2})").unwrap();
+        self.content = if re_status_date.is_match(self.content.as_str()) {
+            re_status_date
+                .replace(
+                    self.content.as_str(),
+                    format!("${{1}}{}", self.date).as_str(),
+                )
+                .as_ref()
+                .to_owned()
+        } else {
+            let re = Regex::new(r"(\d{4})-(\d{2})-(\d{2})").unwrap();
+            re.replace(self.content.as_str(), self.date.as_str())
+                .as_ref()
+                .to_owned()
+        };
+    }
+
+    pub fn update_title(&mut self, title: &str) {
+        let new_title = "".to_owned() + title;
+
+        self.content = self
+            .content
+            .replacen(self.title.as_str(), new_title.as_str(), 1);
+        self.title = new_title;
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = normalize_tag(tag);
+        self.tags_array.iter().any(|t| t.trim() == tag)
+    }
+
+    /// Returns true if the ADR is tagged with `tag` or with one of its descendants (e.g. `security/authn` matches `security`).
+    pub fn matches_tag(&self, tag: &str) -> bool {
+        self.tags_array.iter().any(|t| tag_matches(t, tag))
+    }
+
+    /// Adds the `[tags]#tag#` markup right after the last tag of the ADR (or at the end of the content if the ADR has no tag yet).
+    /// An alias is replaced by the name of its tag. Returns false if the ADR is already tagged with `tag`, or an
+    /// `InvalidInput` error if `tag` is not part of the controlled vocabulary `vocabulary` (cf. `check_tag`).
+    pub fn add_tag(&mut self, tag: &str, vocabulary: &[TagDefinition]) -> io::Result<bool> {
+        lazy_static! {
+            static ref RE_TAG: Regex = Regex::new(r"\[tags]\#[^#]+\#").unwrap();
+        }
+
+        let tag = normalize_tag(&check_tag(vocabulary, tag)?);
+        let tag = tag.as_str();
+        if tag.is_empty() || self.has_tag(tag) {
+            debug!(
+                get_logger(),
+                "ADR [{}] is already tagged with [{}]",
+                self.path(),
+                tag
+            );
+            return Ok(false);
+        }
+
+        let new_tag = format!("[tags]#{}#", tag);
+        let last_tag_end = RE_TAG.find_iter(&self.content).map(|m| m.end()).last();
+        match last_tag_end {
+            Some(end) => self
+                .content
+                .insert_str(end, format!(" {}", new_tag).as_str()),
+            None => {
+                let content = self.content.trim_end().to_string();
+                self.content = format!("{}\n\n{}\n", content, new_tag);
+            }
+        };
+
+        self.refresh_tags();
+        Ok(true)
+    }
+
+    /// Removes every `[tags]#tag#` markup of the ADR. Returns false if the ADR is not tagged with `tag`.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        self.retag(tag, "")
+    }
 
-        self.date = new_date;
-        let re = Regex::new(r"(\d{4})-(\d{2})-(\d{2})").unwrap();
-        self.content = re
-            .replace(self.content.as_str(), self.date.as_str())
-            .as_ref()
-            .to_owned();
+    /// Replaces the tag `old` by `new`. If the ADR is already tagged with `new`, `old` is simply removed so that the tag is not duplicated.
+    /// An alias is replaced by the name of its tag. Returns false if the ADR is not tagged with `old`, or an
+    /// `InvalidInput` error if `new` is not part of the controlled vocabulary `vocabulary` (cf. `check_tag`).
+    pub fn replace_tag(
+        &mut self,
+        old: &str,
+        new: &str,
+        vocabulary: &[TagDefinition],
+    ) -> io::Result<bool> {
+        let new = check_tag(vocabulary, new)?;
+        Ok(self.retag(old, &new))
     }
 
-    pub fn update_title(&mut self, title: &str) {
-        let new_title = "".to_owned() + title;
+    /// Replaces the tag `old` by `new`, or removes it if `new` is empty
+    fn retag(&mut self, old: &str, new: &str) -> bool {
+        lazy_static! {
+            static ref RE_TAG_WITH_SPACE: Regex = Regex::new(r"( ?)\[tags]\#([^#]+)\#").unwrap();
+        }
 
-        self.content = self
-            .content
-            .replacen(self.title.as_str(), new_title.as_str(), 1);
-        self.title = new_title;
+        let old = normalize_tag(old);
+        let new = normalize_tag(new);
+        if !self.has_tag(&old) || old == new {
+            return false;
+        }
+
+        let remove_only = new.is_empty() || self.has_tag(&new);
+        self.content = RE_TAG_WITH_SPACE
+            .replace_all(self.content.as_str(), |caps: &regex::Captures| {
+                if normalize_tag(&caps[2]) != old {
+                    caps[0].to_string()
+                } else if remove_only {
+                    String::new()
+                } else {
+                    format!("{}[tags]#{}#", &caps[1], new)
+                }
+            })
+            .into_owned();
+
+        self.refresh_tags();
+        true
+    }
+
+    fn refresh_tags(&mut self) {
+        let tags = Adr::get_tags(&self.content);
+        self.tags = tags.0;
+        self.tags_array = tags.1;
     }
 }
 
@@ -588,6 +1594,12 @@ impl Clone for Adr {
             date: String::from(self.date.as_str()),
             tags: String::from(self.tags.as_str()),
             tags_array: self.tags_array.clone(),
+            deciders: self.deciders.clone(),
+            history: self.history.clone(),
+            revisit: self.revisit.clone(),
+            reviews: self.reviews.clone(),
+            git_history: self.git_history.clone(),
+            workspace: self.workspace.clone(),
             status: self.state.status.clone(),
             state: self.state.clone(),
         }
@@ -596,6 +1608,8 @@ impl Clone for Adr {
 
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum TransitionStatus {
+    REVIEW,
+    REJECTED,
     DECIDED,
     COMPLETED,
     COMPLETES,
@@ -620,6 +1634,8 @@ impl TransitionStatus {
 
     pub fn as_str(&self) -> &'static str {
         match *self {
+            TransitionStatus::REVIEW => "review",
+            TransitionStatus::REJECTED => "rejected",
             TransitionStatus::DECIDED => "decided",
             TransitionStatus::COMPLETED => "completed",
             TransitionStatus::COMPLETES => "completes",
@@ -632,6 +1648,8 @@ impl TransitionStatus {
 
     pub fn from_str(val: String) -> TransitionStatus {
         match val.as_str() {
+            "review" => TransitionStatus::REVIEW,
+            "rejected" => TransitionStatus::REJECTED,
             "decided" => TransitionStatus::DECIDED,
             "completed" => TransitionStatus::COMPLETED,
             "completes" => TransitionStatus::COMPLETES,
@@ -643,9 +1661,10 @@ impl TransitionStatus {
     }
 }
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Copy, Clone, Serialize, Deserialize)]
 pub enum Status {
     WIP,
+    REVIEW,
     DECIDED,
     COMPLETED,
     COMPLETES,
@@ -662,9 +1681,44 @@ impl Default for Status {
 }
 
 impl Status {
+    /// The position of the status along the lifecycle (cf. `AdrState`), used to sort the ADRs by status.
+    pub fn lifecycle_order(&self) -> usize {
+        match *self {
+            Status::WIP => 0,
+            Status::REVIEW => 1,
+            Status::DECIDED => 2,
+            Status::COMPLETES => 3,
+            Status::SUPERSEDES => 3,
+            Status::COMPLETED => 4,
+            Status::SUPERSEDED => 5,
+            Status::CANCELLED => 6,
+            Status::NONE => 7,
+        }
+    }
+
+    /// Returns true if the state machine (cf. `AdrState`) allows to go from this status to `next` with one transition
+    pub fn can_transition_to(&self, next: Status) -> bool {
+        [
+            TransitionStatus::REVIEW,
+            TransitionStatus::REJECTED,
+            TransitionStatus::DECIDED,
+            TransitionStatus::COMPLETED,
+            TransitionStatus::COMPLETES,
+            TransitionStatus::SUPERSEDED,
+            TransitionStatus::SUPERSEDES,
+            TransitionStatus::CANCELLED,
+        ]
+        .iter()
+        .any(|transition| {
+            let mut state = AdrState::build(*self);
+            state.transition(*transition) && state.status == next
+        })
+    }
+
     pub fn as_str(&self) -> &'static str {
         match *self {
             Status::WIP => "wip",
+            Status::REVIEW => "review",
             Status::DECIDED => "decided",
             Status::COMPLETED => "completed",
             Status::COMPLETES => "completes",
@@ -678,6 +1732,7 @@ impl Status {
     pub fn from_str(val: String) -> Status {
         match val.as_str() {
             "wip" => Status::WIP,
+            "review" => Status::REVIEW,
             "decided" => Status::DECIDED,
             "completed" => Status::COMPLETED,
             "completes" => Status::COMPLETES,
@@ -695,7 +1750,7 @@ pub trait State {
     fn build(status: Status) -> AdrState;
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct AdrState {
     status: Status,
 }
@@ -717,6 +1772,10 @@ impl State for AdrState {
 
         let next_status = match current_status {
             Status::WIP => match transition {
+                TransitionStatus::REVIEW => {
+                    self.status = Status::REVIEW;
+                    Status::REVIEW
+                }
                 TransitionStatus::DECIDED => {
                     self.status = Status::DECIDED;
                     Status::DECIDED
@@ -730,6 +1789,24 @@ impl State for AdrState {
                     Status::WIP
                 }
             },
+            Status::REVIEW => match transition {
+                TransitionStatus::DECIDED => {
+                    self.status = Status::DECIDED;
+                    Status::DECIDED
+                }
+                TransitionStatus::REJECTED => {
+                    self.status = Status::WIP;
+                    Status::WIP
+                }
+                TransitionStatus::CANCELLED => {
+                    self.status = Status::CANCELLED;
+                    Status::CANCELLED
+                }
+                _ => {
+                    has_been_modified = false;
+                    Status::REVIEW
+                }
+            },
             Status::DECIDED => match transition {
                 TransitionStatus::COMPLETED => {
                     self.status = Status::COMPLETED;
@@ -900,6 +1977,38 @@ mod tests {
         assert_eq!(update_true, true);
     }
 
+    #[test]
+    fn test_adr_add_history() {
+        let mut adr_sut = super::Adr::from(
+            "base_path".to_string(),
+            "a_path".to_string(),
+            String::from("== ADR-MVA-507 Decide about ...\n\n*Status:* {wip} *Date:* 2019-10-28\n// adr-history: 2019-10-28 wip\n\n[cols=\",\",options=..."),
+        );
+        assert_eq!(
+            vec![AdrTransition {
+                date: String::from("2019-10-28"),
+                status: Status::WIP
+            }],
+            adr_sut.history
+        );
+
+        adr_sut.update_status(TransitionStatus::DECIDED);
+        adr_sut.add_reference("by adr-num-123");
+
+        let today = Utc::now().date_naive().format("%Y-%m-%d").to_string();
+        assert_eq!(2, adr_sut.history.len());
+        assert_eq!(Status::DECIDED, adr_sut.history[1].status);
+        assert_eq!(today, adr_sut.history[1].date);
+        assert!(adr_sut.content.contains(
+            format!(
+                "{{decided}} by adr-num-123 *Date:* {0}\n// adr-history: 2019-10-28 wip\n// adr-history: {0} decided\n\n[cols",
+                today
+            )
+            .as_str()
+        ));
+        assert_eq!(adr_sut.history, super::Adr::get_history(&adr_sut.content));
+    }
+
     #[test]
     fn test_adr_add_reference() {
         let mut adr_sut = Adr::new();
@@ -926,6 +2035,7 @@ mod tests {
 
         let expected_status = "{decided} by adr-num-123 *Date:* 2019-10-28";
         assert_eq!(adr_sut.content.contains(expected_status), true);
+        assert_eq!(vec!["by", "adr-num-123"], adr_sut.references());
     }
 
     #[test]
@@ -1031,6 +2141,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_state_machine_wip_to_review() {
+        let mut state = super::AdrState::build(super::Status::WIP);
+        assert!(state.transition(super::TransitionStatus::REVIEW));
+        assert_eq!(
+            state,
+            super::AdrState {
+                status: super::Status::REVIEW
+            }
+        );
+        assert!(!state.transition(super::TransitionStatus::COMPLETED));
+        assert!(state.transition(super::TransitionStatus::REJECTED));
+        assert_eq!(
+            state,
+            super::AdrState {
+                status: super::Status::WIP
+            }
+        );
+        assert!(!state.transition(super::TransitionStatus::REJECTED));
+        state.transition(super::TransitionStatus::REVIEW);
+        assert!(state.transition(super::TransitionStatus::DECIDED));
+        assert_eq!(
+            state,
+            super::AdrState {
+                status: super::Status::DECIDED
+            }
+        );
+    }
+
     #[test]
     fn test_state_machine_decided_to_cancelled() {
         let mut state = super::AdrState::build(super::Status::DECIDED);
@@ -1085,6 +2224,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_status_can_transition_to() {
+        assert!(Status::WIP.can_transition_to(Status::REVIEW));
+        assert!(Status::REVIEW.can_transition_to(Status::WIP));
+        assert!(Status::DECIDED.can_transition_to(Status::SUPERSEDED));
+        assert!(!Status::WIP.can_transition_to(Status::SUPERSEDED));
+        assert!(!Status::DECIDED.can_transition_to(Status::WIP));
+        assert!(!Status::CANCELLED.can_transition_to(Status::DECIDED));
+    }
+
     #[test]
     fn test_state_machine_decided_to_fail() {
         let mut state = super::AdrState::build(super::Status::DECIDED);
@@ -1305,6 +2454,26 @@ mod tests {
         assert_eq!(adr_sut.status, super::Status::WIP);
     }
 
+    #[test]
+    fn test_get_deciders() {
+        let content = "
+        == ADR-MVA-507 Decide about ...
+
+        *Status:* {wip}  *Date:* 2019-10-28
+
+        |===
+        |role |people
+        |_Decision Makers_ |@John Smith, @Jane Doe
+        |_Experts_ |@John Smith
+        |===";
+
+        assert_eq!(
+            vec!["@John Smith", "@Jane Doe"],
+            super::Adr::get_deciders(content)
+        );
+        assert_eq!(0, super::Adr::get_deciders(ADOC_TMPL_TAG).len());
+    }
+
     #[test]
     fn test_build_adr() {
         let src = match TempDir::new("my_src_folder") {
@@ -1351,6 +2520,7 @@ mod tests {
             adr_search_index: format!("{}", src.path().display()),
             use_id_prefix: false,
             id_prefix_width: 3,
+            ..AdrToolConfig::default()
         };
 
         let to = PathBuf::from(src.path()).join("template.adoc");
@@ -1387,6 +2557,7 @@ mod tests {
             adr_search_index: format!("{}", src.path().display()),
             use_id_prefix: true,
             id_prefix_width: 3,
+            ..AdrToolConfig::default()
         };
 
         let to = PathBuf::from(src.path()).join("template.adoc");
@@ -1429,6 +2600,7 @@ mod tests {
             adr_search_index: format!("{}", src.path().display()),
             use_id_prefix: true,
             id_prefix_width: 3,
+            ..AdrToolConfig::default()
         };
 
         let to = PathBuf::from(src.path()).join("template.adoc");
@@ -1470,6 +2642,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_create_adr_with_tags() {
+        let src = match TempDir::new("my_src_folder") {
+            Ok(src) => src,
+            Err(why) => {
+                println!("Unable to get src dir [{}]", why);
+                panic!("{}", why);
+            }
+        };
+
+        //set config
+        let config = AdrToolConfig {
+            adr_src_dir: format!("{}", src.path().display()),
+            adr_template_dir: format!("{}", src.path().display()),
+            adr_template_file: String::from("template.adoc"),
+            use_id_prefix: false,
+            allowed_tags: vec![TagDefinition {
+                name: String::from("security"),
+                description: String::from("Authentication, authorization, crypto"),
+                aliases: vec![String::from("sec")],
+            }],
+            ..AdrToolConfig::default()
+        };
+
+        let to = PathBuf::from(src.path()).join("template.adoc");
+        fs::write(to.as_path(), ADOC_TMPL_TAG).unwrap();
+
+        //unknown tags are rejected
+        let created = super::create_adr_with_tags(config.clone(), None, "my decision", &["secure"]);
+        assert_eq!(io::ErrorKind::InvalidInput, created.unwrap_err().kind());
+        assert!(!src.path().join("my-decision.adoc").exists());
+
+        //aliases are replaced by the name of the tag
+        let created = super::create_adr_with_tags(config, None, "my decision", &["sec"]);
+        assert_eq!(Some(src.path().join("my-decision.adoc")), created.unwrap());
+        let adr = super::build_adr(src.path(), &src.path().join("my-decision.adoc")).unwrap();
+        assert_eq!("#security ", adr.tags);
+    }
+
     #[test]
     fn test_get_tags_popularity() {
         let src = match TempDir::new("my_src_folder") {
@@ -1500,6 +2711,83 @@ mod tests {
         assert_eq!(Some(&3), tags.get("tag3 "));
     }
 
+    #[test]
+    fn test_scan_adr_wo_content() {
+        let src = match TempDir::new("my_src_folder") {
+            Ok(src) => src,
+            Err(why) => {
+                println!("Unable to get src dir [{}]", why);
+                panic!("{}", why);
+            }
+        };
+        for name in ["003-ADR-3.adoc", "001-ADR-1.adoc", "002-ADR-2.adoc"].iter() {
+            fs::write(src.path().join(name), ADOC_TMPL_TAG).unwrap();
+        }
+
+        let mut adrs = super::scan_adr_with_cache(src.path(), None, false).unwrap();
+        assert_eq!(
+            vec![1, 2, 3],
+            adrs.iter().map(|adr| adr.file_id).collect::<Vec<usize>>()
+        );
+        assert!(adrs.iter().all(|adr| adr.content.is_empty()));
+        assert_eq!(3, adrs[0].tags_array.len());
+
+        //the content is loaded on demand
+        assert_eq!(ADOC_TMPL_TAG, adrs[0].load_content().unwrap());
+
+        //with a cache, whether the files are parsed or cached
+        let cache_path = src.path().join(".cache");
+        for _ in 0..2 {
+            let adrs = super::scan_adr_with_cache(src.path(), Some(&cache_path), false).unwrap();
+            assert_eq!(3, adrs.len());
+            assert!(adrs.iter().all(|adr| adr.content.is_empty()));
+            assert!(cache_path.exists());
+        }
+    }
+
+    #[test]
+    fn test_get_tags_rollup() {
+        let src = match TempDir::new("my_src_folder") {
+            Ok(src) => src,
+            Err(why) => {
+                println!("Unable to get src dir [{}]", why);
+                panic!("{}", why);
+            }
+        };
+
+        let to = PathBuf::from(src.path()).join("001-ADR-1.adoc");
+        fs::write(
+            to.as_path(),
+            "= ADR 1\n\n[tags]#security/authn# [tags]#security / crypto# [tags]#infra/network#",
+        )
+        .unwrap();
+        let to = PathBuf::from(src.path()).join("002-ADR-2.adoc");
+        fs::write(to.as_path(), "= ADR 2\n\n[tags]#security/crypto#").unwrap();
+
+        let tags = super::get_tags_rollup(src.path()).unwrap();
+        assert_eq!(
+            vec![
+                "infra",
+                "infra/network",
+                "security",
+                "security/authn",
+                "security/crypto"
+            ],
+            tags.keys().collect::<Vec<&String>>()
+        );
+        assert_eq!(Some(&2), tags.get("security"));
+        assert_eq!(Some(&1), tags.get("security/authn"));
+        assert_eq!(Some(&2), tags.get("security/crypto"));
+        assert_eq!(Some(&1), tags.get("infra"));
+
+        let adr = super::build_adr(src.path(), &src.path().join("001-ADR-1.adoc")).unwrap();
+        assert_eq!("#security/authn #security/crypto #infra/network ", adr.tags);
+        assert!(adr.matches_tag("security"));
+        assert!(adr.matches_tag("infra/network"));
+        assert!(!adr.matches_tag("infra/storage"));
+        assert!(!adr.matches_tag("secu"));
+    }
+
     #[test]
     fn test_build_adr_wo_tags() {
         let content = "
@@ -1537,7 +2825,7 @@ mod tests {
 
         assert_eq!(adr_sut.date, "2019-10-28");
 
-        let date = Utc::today();
+        let date = Utc::now().date_naive();
         adr_sut.update_date(date);
 
         let date = date.format("%Y-%m-%d");
@@ -1547,6 +2835,42 @@ mod tests {
         assert_eq!(true, adr_sut.content.contains(contain.as_str()));
     }
 
+    #[test]
+    fn test_get_revisit() {
+        let content = ":revisit: 2021-01-15
+        == ADR-MVA-507 Decide about ...
+
+        *Status:* {decided}  *Date:* 2019-10-28
+        ....";
+
+        let mut adr_sut = super::Adr::from(
+            "base_path".to_string(),
+            "a_path".to_string(),
+            content.to_string(),
+        );
+
+        assert_eq!(adr_sut.revisit, Some(String::from("2021-01-15")));
+        assert_eq!(adr_sut.date, "2019-10-28");
+
+        adr_sut.update_date(NaiveDate::from_ymd_opt(2020, 2, 3).unwrap());
+        assert!(adr_sut.content.contains(":revisit: 2021-01-15"));
+        assert!(adr_sut.content.contains("*Date:* 2020-02-03"));
+
+        let adr_sut = super::Adr::from(
+            "base_path".to_string(),
+            "a_path".to_string(),
+            content.replace(":revisit:", ":review-by:"),
+        );
+        assert_eq!(adr_sut.revisit, Some(String::from("2021-01-15")));
+
+        let adr_sut = super::Adr::from(
+            "base_path".to_string(),
+            "a_path".to_string(),
+            content.replace(":revisit: 2021-01-15", ""),
+        );
+        assert_eq!(adr_sut.revisit, None);
+    }
+
     #[test]
     fn test_update_title() {
         let content = "
@@ -1652,4 +2976,224 @@ mod tests {
             TransitionStatus::revert(TransitionStatus::SUPERSEDES)
         );
     }
+
+    #[test]
+    fn test_adr_add_tag() {
+        let mut adr_sut = super::Adr::from(
+            "base_path".to_string(),
+            "a_path".to_string(),
+            String::from(ADOC_TMPL_TAG),
+        );
+
+        assert!(adr_sut.add_tag("tag4", &[]).unwrap());
+        assert!(!adr_sut.add_tag("tag1", &[]).unwrap());
+        assert_eq!(adr_sut.tags, "#tag1 #tag2 #tag3 #tag4 ");
+        assert!(adr_sut
+            .content
+            .contains("[tags]#tag1# [tags]#tag2# [tags]#tag3# [tags]#tag4#"));
+
+        let mut adr_sut = super::Adr::from(
+            "base_path".to_string(),
+            "a_path".to_string(),
+            String::from(ADOC_TMPL_NOTAG),
+        );
+        assert!(adr_sut.add_tag("tag1", &[]).unwrap());
+        assert_eq!(adr_sut.tags, "#tag1 ");
+        assert!(adr_sut.content.ends_with("\n\n[tags]#tag1#\n"));
+
+        //controlled vocabulary
+        let vocabulary = vec![TagDefinition {
+            name: String::from("security"),
+            description: String::from(""),
+            aliases: vec![String::from("sec")],
+        }];
+        assert!(adr_sut.add_tag("secuirty", &vocabulary).is_err());
+        assert!(adr_sut.add_tag("sec", &vocabulary).unwrap());
+        assert_eq!(adr_sut.tags, "#tag1 #security ");
+    }
+
+    #[test]
+    fn test_adr_remove_tag() {
+        let mut adr_sut = super::Adr::from(
+            "base_path".to_string(),
+            "a_path".to_string(),
+            String::from(ADOC_TMPL_TAG),
+        );
+
+        assert!(adr_sut.remove_tag("tag2"));
+        assert!(!adr_sut.remove_tag("tag2"));
+        assert_eq!(adr_sut.tags, "#tag1 #tag3 ");
+        assert!(adr_sut.content.contains("[tags]#tag1# [tags]#tag3#"));
+    }
+
+    #[test]
+    fn test_adr_replace_tag() {
+        let mut adr_sut = super::Adr::from(
+            "base_path".to_string(),
+            "a_path".to_string(),
+            String::from(ADOC_TMPL_TAG),
+        );
+
+        assert!(adr_sut.replace_tag("tag2", "security", &[]).unwrap());
+        assert_eq!(adr_sut.tags, "#tag1 #security #tag3 ");
+
+        //tag3 is merged into the already present tag1
+        assert!(adr_sut.replace_tag("tag3", "tag1", &[]).unwrap());
+        assert_eq!(adr_sut.tags, "#tag1 #security ");
+        assert!(!adr_sut.replace_tag("unknown", "tag1", &[]).unwrap());
+
+        //tags whose name is part of the markup
+        let mut adr_sut = super::Adr::from(
+            "base_path".to_string(),
+            "a_path".to_string(),
+            String::from("= title\n\n[tags]#tags# [tags]#s#\n"),
+        );
+        assert!(adr_sut.replace_tag("tags", "labels", &[]).unwrap());
+        assert!(adr_sut.replace_tag("s", "security", &[]).unwrap());
+        assert_eq!(adr_sut.tags, "#labels #security ");
+        assert!(adr_sut
+            .content
+            .contains("\n[tags]#labels# [tags]#security#\n"));
+    }
+
+    #[test]
+    fn test_merge_tags() {
+        let src = match TempDir::new("my_src_folder") {
+            Ok(src) => src,
+            Err(why) => {
+                println!("Unable to get src dir [{}]", why);
+                panic!("{}", why);
+            }
+        };
+
+        let to = PathBuf::from(src.path()).join("001-ADR-1.adoc");
+        fs::write(to.as_path(), ADOC_TMPL_TAG).unwrap();
+        let to = PathBuf::from(src.path()).join("002-ADR-2.adoc");
+        fs::write(to.as_path(), ADOC_TMPL_NOTAG).unwrap();
+
+        //dry run does not modify the files
+        let changed =
+            super::merge_tags(src.path(), &["tag1", "tag2"], "security", &[], true).unwrap();
+        assert_eq!(1, changed.len());
+        assert_eq!(
+            ADOC_TMPL_TAG,
+            fs::read_to_string(src.path().join("001-ADR-1.adoc")).unwrap()
+        );
+
+        //into a tag which is not part of the vocabulary
+        let vocabulary = vec![TagDefinition {
+            name: String::from("security"),
+            description: String::from(""),
+            aliases: vec![],
+        }];
+        assert!(super::merge_tags(src.path(), &["tag1"], "secuirty", &vocabulary, false).is_err());
+
+        let changed = super::merge_tags(
+            src.path(),
+            &["tag1", "tag2"],
+            "security",
+            &vocabulary,
+            false,
+        )
+        .unwrap();
+        assert_eq!(1, changed.len());
+        let tags = super::get_tags_popularity(src.path()).unwrap();
+        assert_eq!(2, tags.len());
+        assert_eq!(Some(&1), tags.get("security "));
+        assert_eq!(Some(&1), tags.get("tag3 "));
+
+        let changed = super::rename_tag(src.path(), "security", "security", &[], false).unwrap();
+        assert_eq!(0, changed.len());
+    }
+
+    #[test]
+    fn test_adr_add_review() {
+        let mut adr_sut = super::Adr::from(
+            "base_path".to_string(),
+            "a_path".to_string(),
+            String::from("== ADR-MVA-507 Decide about ...\n\n*Status:* {review} *Date:* 2019-10-28\n// adr-history: 2019-10-28 review\n// adr-review: 2019-10-28 requested @bob\n\n[cols=\",\",options=..."),
+        );
+        assert_eq!(1, adr_sut.reviews.len());
+        assert_eq!(ReviewAction::REQUESTED, adr_sut.reviews[0].action);
+        assert_eq!("@bob", adr_sut.reviews[0].reviewer);
+
+        let today = NaiveDate::from_ymd_opt(2019, 10, 29).unwrap();
+        //only the distinct requested reviewers count
+        adr_sut.add_review(today, ReviewAction::APPROVED, "alice", "");
+        assert_eq!(0, adr_sut.approvals().len());
+        adr_sut.add_review(today, ReviewAction::APPROVED, "bob", "");
+        adr_sut.add_review(today, ReviewAction::APPROVED, "@Bob", "");
+        assert_eq!(vec![String::from("@bob")], adr_sut.approvals());
+
+        adr_sut.add_review(
+            today,
+            ReviewAction::REJECTED,
+            "@John Smith",
+            "too expensive",
+        );
+        assert_eq!(0, adr_sut.approvals().len());
+        adr_sut.add_review(today, ReviewAction::APPROVED, "@bob", "");
+        assert_eq!(vec![String::from("@bob")], adr_sut.approvals());
+
+        assert!(adr_sut.content.contains("// adr-review: 2019-10-28 requested @bob\n// adr-review: 2019-10-29 approved @alice\n// adr-review: 2019-10-29 approved @bob\n// adr-review: 2019-10-29 approved @Bob\n// adr-review: 2019-10-29 rejected @John Smith: too expensive\n// adr-review: 2019-10-29 approved @bob\n\n[cols"));
+        let reviews = super::Adr::get_reviews(&adr_sut.content);
+        assert_eq!(adr_sut.reviews, reviews);
+        assert_eq!("@John Smith", reviews[4].reviewer);
+        assert_eq!("too expensive", reviews[4].note);
+        assert_eq!(1, adr_sut.history.len());
+    }
+
+    #[test]
+    fn test_review_workflow() {
+        let src = match TempDir::new("my_src_folder") {
+            Ok(src) => src,
+            Err(why) => {
+                println!("Unable to get src dir [{}]", why);
+                panic!("{}", why);
+            }
+        };
+
+        let to = PathBuf::from(src.path()).join("001-ADR-1.adoc");
+        fs::write(to.as_path(), ADOC_TMPL_NOTAG.replace("{decided}", "{wip}")).unwrap();
+        let path = to.to_str().unwrap();
+
+        //a wip ADR cannot be decided without the quorum
+        assert!(!super::approve_review(src.path(), path, "@alice").unwrap());
+        assert!(!super::transition_to_decided_with_quorum(src.path(), path, 1).unwrap());
+
+        assert!(super::request_review(src.path(), path, &["@alice", "@bob"]).unwrap());
+        assert!(!super::request_review(src.path(), path, &[]).unwrap());
+        //not requested
+        assert!(!super::approve_review(src.path(), path, "@carol").unwrap());
+        assert!(super::approve_review(src.path(), path, "@alice").unwrap());
+        assert!(super::reject_review(src.path(), path, "@bob", "missing options").unwrap());
+
+        let adr = super::build_adr(src.path(), to.as_path()).unwrap();
+        assert_eq!(Status::WIP, adr.status);
+        assert_eq!(0, adr.approvals().len());
+        assert_eq!("missing options", adr.reviews[3].note);
+
+        super::request_review(src.path(), path, &[]).unwrap();
+        super::approve_review(src.path(), path, "@alice").unwrap();
+        super::approve_review(src.path(), path, "alice").unwrap();
+        assert!(!super::transition_to_decided_with_quorum(src.path(), path, 2).unwrap());
+        super::approve_review(src.path(), path, "@bob").unwrap();
+        assert!(super::transition_to_decided_with_quorum(src.path(), path, 2).unwrap());
+
+        let adr = super::build_adr(src.path(), to.as_path()).unwrap();
+        assert_eq!(Status::DECIDED, adr.status);
+        assert_eq!(
+            vec![Status::REVIEW, Status::WIP, Status::REVIEW, Status::DECIDED],
+            adr.history
+                .iter()
+                .map(|t| t.status)
+                .collect::<Vec<Status>>()
+        );
+
+        //without quorum, reviews are optional
+        let to = PathBuf::from(src.path()).join("002-ADR-2.adoc");
+        fs::write(to.as_path(), ADOC_TMPL_NOTAG.replace("{decided}", "{wip}")).unwrap();
+        let path = to.to_str().unwrap();
+        assert!(super::transition_to_decided_with_quorum(src.path(), path, 0).unwrap());
+    }
 }
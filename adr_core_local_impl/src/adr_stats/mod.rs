@@ -0,0 +1,179 @@
+extern crate slog;
+use slog::*;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+extern crate adr_config;
+
+use crate::adr_filter::parse_date;
+use crate::adr_repo::*;
+
+fn get_logger() -> slog::Logger {
+    adr_config::logger::get_logger()
+}
+
+/// Statistics and flow metrics of a decision log
+#[derive(Debug, Default, Serialize)]
+pub struct AdrStats {
+    pub total: usize,
+    pub by_status: BTreeMap<String, usize>,
+    pub by_tag: BTreeMap<String, usize>,
+    pub by_directory: BTreeMap<String, usize>,
+    /// Number of decisions (i.e. transitions to decided) per month (`YYYY-MM`)
+    pub decisions_per_month: BTreeMap<String, usize>,
+    /// Median number of days from wip to decided, for the ADRs with a recorded history
+    pub median_days_to_decided: Option<f64>,
+    /// Number of superseded ADRs compared to the number of ADRs which have been decided
+    pub supersede_rate: Option<f64>,
+}
+
+impl AdrStats {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+/// Computes the statistics of the provided ADRs. Dates of the transitions are taken from the recorded history
+/// (cf. `Adr::get_history`), the git history (cf. `adr_git::load_git_history`) and, for ADRs without history,
+/// from `Adr.date` when the ADR is still decided.
+pub fn get_stats(adrs: &[Adr]) -> AdrStats {
+    let mut stats = AdrStats::default();
+    let mut days_to_decided = Vec::new();
+    let mut decided = 0;
+    let mut superseded = 0;
+
+    for adr in adrs {
+        stats.total += 1;
+        *stats
+            .by_status
+            .entry(adr.status.as_str().to_string())
+            .or_insert(0) += 1;
+        for tag in adr.tags_array.iter() {
+            *stats.by_tag.entry(tag.trim().to_string()).or_insert(0) += 1;
+        }
+        let directory = match Path::new(&adr.file_path).parent() {
+            Some(parent) if parent != Path::new("") => format!("{}", parent.display()),
+            _ => String::from("."),
+        };
+        *stats.by_directory.entry(directory).or_insert(0) += 1;
+
+        match adr.status {
+            Status::DECIDED | Status::COMPLETED | Status::COMPLETES | Status::SUPERSEDES => {
+                decided += 1
+            }
+            Status::SUPERSEDED => {
+                decided += 1;
+                superseded += 1;
+            }
+            _ => (),
+        };
+
+        let decided_at = match adr
+            .status_date(Status::DECIDED)
+            .and_then(|date| parse_date(&date))
+        {
+            Some(date) => Some(date),
+            None if adr.status == Status::DECIDED => parse_date(&adr.date),
+            None => None,
+        };
+        if let Some(decided_at) = decided_at {
+            *stats
+                .decisions_per_month
+                .entry(decided_at.format("%Y-%m").to_string())
+                .or_insert(0) += 1;
+
+            if let Some(created_at) = adr.created_at().and_then(|date| parse_date(&date)) {
+                days_to_decided.push((decided_at - created_at).num_days());
+            }
+        }
+    }
+
+    stats.median_days_to_decided = median(days_to_decided);
+    if decided > 0 {
+        stats.supersede_rate = Some(superseded as f64 / decided as f64);
+    }
+
+    debug!(get_logger(), "Got stats [{:?}]", stats);
+
+    stats
+}
+
+fn median(mut values: Vec<i64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+
+    values.sort();
+    let middle = values.len() / 2;
+    match values.len() % 2 {
+        0 => Some((values[middle - 1] + values[middle]) as f64 / 2.0),
+        _ => Some(values[middle] as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adr_stats::*;
+
+    fn adr(file_path: &str, status: &str, date: &str, history: &str) -> Adr {
+        Adr::from(
+            String::from("/adr"),
+            String::from(file_path),
+            format!(
+                "= title\n\n*Status:* {{{}}} *Date:* {}\n{}\n\n[tags]#security#",
+                status, date, history
+            ),
+        )
+    }
+
+    #[test]
+    fn test_get_stats() {
+        let adrs = vec![
+            adr(
+                "01-a.adoc",
+                "decided",
+                "2020-01-10",
+                "// adr-history: 2020-01-01 wip\n// adr-history: 2020-01-10 decided",
+            ),
+            adr(
+                "infra/02-b.adoc",
+                "superseded",
+                "2020-03-01",
+                "// adr-history: 2020-01-01 wip\n// adr-history: 2020-01-05 decided\n// adr-history: 2020-03-01 superseded",
+            ),
+            adr(
+                "infra/03-c.adoc",
+                "supersedes",
+                "2020-03-01",
+                "// adr-history: 2020-02-01 wip\n// adr-history: 2020-02-21 decided\n// adr-history: 2020-03-01 supersedes",
+            ),
+            adr("04-d.adoc", "decided", "2020-02-14", ""),
+            adr("05-e.adoc", "wip", "2020-03-02", ""),
+        ];
+
+        let stats = get_stats(&adrs);
+        assert_eq!(5, stats.total);
+        assert_eq!(Some(&2), stats.by_status.get("decided"));
+        assert_eq!(Some(&1), stats.by_status.get("wip"));
+        assert_eq!(Some(&5), stats.by_tag.get("security"));
+        assert_eq!(Some(&3), stats.by_directory.get("."));
+        assert_eq!(Some(&2), stats.by_directory.get("infra"));
+        assert_eq!(Some(&2), stats.decisions_per_month.get("2020-01"));
+        assert_eq!(Some(&2), stats.decisions_per_month.get("2020-02"));
+        //4, 9 and 20 days
+        assert_eq!(Some(9.0), stats.median_days_to_decided);
+        assert_eq!(Some(0.25), stats.supersede_rate);
+
+        let json = stats.to_json();
+        assert!(json.contains("\"median_days_to_decided\": 9.0"));
+    }
+
+    #[test]
+    fn test_get_stats_wo_adr() {
+        let stats = get_stats(&[]);
+        assert_eq!(0, stats.total);
+        assert_eq!(None, stats.median_days_to_decided);
+        assert_eq!(None, stats.supersede_rate);
+    }
+}
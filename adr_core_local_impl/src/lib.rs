@@ -1,4 +1,16 @@
 #[macro_use]
 extern crate lazy_static;
+#[macro_use]
+extern crate serde_derive;
 
+pub mod adr_cache;
+pub mod adr_changelog;
+pub mod adr_discovery;
+pub mod adr_due;
+pub mod adr_federation;
+pub mod adr_filter;
+pub mod adr_git;
+pub mod adr_lint;
+pub mod adr_merge;
 pub mod adr_repo;
+pub mod adr_stats;
@@ -175,7 +175,7 @@ mod check_transitions_and_lifecycle_of_adr {
             match "n/a" == by {
                 true => {
                     println!("calling transition_to() with [{}] [{}] [{}]", transition, adr.name, by);
-                    match adr_core::adr_repo::transition_to(TransitionStatus::from_str(transition), Path::new(&adr.base_path), adr.name.as_str(), "") {
+                    match adr_core::adr_repo::transition_to(TransitionStatus::from_str(transition), Path::new(&adr.base_path), adr.name.as_str(), "", 0) {
                         Ok(transitioned) => adr.has_transitioned = transitioned,
                         Err(why) => panic!(why)
                     };
@@ -183,7 +183,7 @@ mod check_transitions_and_lifecycle_of_adr {
                 false => {
                     let by = format!("{}", PathBuf::from(adr.base_path.as_str()).join(by).display());
                     println!("calling transition_to() with [{}] [{}] [{}]", transition, adr.name, by);
-                    match adr_core::adr_repo::transition_to(TransitionStatus::from_str(transition), Path::new(&adr.base_path), adr.name.as_str(), by.as_str()) {
+                    match adr_core::adr_repo::transition_to(TransitionStatus::from_str(transition), Path::new(&adr.base_path), adr.name.as_str(), by.as_str(), 0) {
                         Ok(transitioned) => adr.has_transitioned = transitioned,
                         Err(why) => panic!(why)
                     };
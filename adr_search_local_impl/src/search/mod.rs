@@ -1,78 +1,325 @@
 use tantivy::collector::TopDocs;
 use tantivy::directory::MmapDirectory;
-use tantivy::query::QueryParser;
+use tantivy::query::{BooleanQuery, Occur, PhraseQuery, Query, QueryParser, TermQuery};
 use tantivy::schema::*;
 use tantivy::Index;
 use tantivy::ReloadPolicy;
 
-use std::path::Path;
-use std::time::Instant;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, UNIX_EPOCH};
 
 extern crate slog;
-extern crate slog_term;
 use slog::*;
 
 extern crate adr_config;
-use adr_config::config::*;
+use adr_config::config::AdrToolConfig;
 
 extern crate adr_core;
 use adr_core::adr_repo::*;
 
 fn get_logger() -> slog::Logger {
-    let cfg: AdrToolConfig = get_config();
+    adr_config::logger::get_logger()
+}
 
-    let decorator = slog_term::TermDecorator::new().build();
-    let drain = slog_term::FullFormat::new(decorator).build().fuse();
-    let drain = slog_async::Async::new(drain).build().fuse();
-    let drain = slog::LevelFilter::new(
-        drain,
-        Level::from_usize(cfg.log_level).unwrap_or(Level::Debug),
-    )
-    .fuse();
+/// The version of the format of the manifest. A manifest with another version is ignored, i.e. all the ADRs are
+/// indexed again.
+const MANIFEST_VERSION: usize = 1;
 
-    slog::Logger::root(drain, o!())
+/// The ADRs of an index, keyed by their path, so that only the ones which have changed are indexed again. It is stored
+/// next to the index, cf. `get_manifest_path`.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct IndexManifest {
+    version: usize,
+    entries: BTreeMap<String, IndexedAdr>,
 }
 
-pub fn build_index(index_path: String, adrs: Vec<Adr>) -> tantivy::Result<()> /*Result<(), ()>*/ {
-    info!(get_logger(), "Building Index in folder [{}]", index_path);
+/// The state of the file of an ADR when it has been indexed
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+struct IndexedAdr {
+    modified_secs: u64,
+    modified_nanos: u32,
+    size: u64,
+    /// The hash of the indexed content (and ID), so that a file which has only been touched is not indexed again
+    hash: String,
+}
 
-    let now = Instant::now();
-    let index_path = Path::new(&index_path);
+/// The changes of an index, cf. `build_index`
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct IndexChanges {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+impl IndexChanges {
+    fn is_empty(&self) -> bool {
+        self.added + self.updated + self.removed == 0
+    }
+}
 
+/// The schema of the index, the ADRs being keyed by the `key` field (i.e. their untokenized path)
+fn get_schema() -> Schema {
     let mut schema_builder = Schema::builder();
     schema_builder.add_text_field("title", TEXT | STORED);
     schema_builder.add_text_field("body", TEXT);
     schema_builder.add_text_field("tags", TEXT | STORED);
     schema_builder.add_text_field("path", TEXT | STORED);
-    let schema = schema_builder.build();
+    schema_builder.add_text_field("id", STRING | STORED);
+    schema_builder.add_text_field("key", STRING);
+    schema_builder.build()
+}
+
+/// Returns the path of the manifest of the index, i.e. `<index>.manifest.json` next to the index directory
+fn get_manifest_path(index_path: &Path) -> PathBuf {
+    let name = match index_path.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => String::from("index"),
+    };
+    index_path.with_file_name(format!("{}.manifest.json", name))
+}
+
+fn load_manifest(path: &Path) -> IndexManifest {
+    let manifest = fs::read_to_string(path)
+        .map_err(|why| format!("{}", why))
+        .and_then(|content| {
+            serde_json::from_str::<IndexManifest>(&content).map_err(|why| format!("{}", why))
+        });
+
+    match manifest {
+        Ok(manifest) if manifest.version == MANIFEST_VERSION => manifest,
+        Ok(_manifest) => IndexManifest::default(),
+        Err(why) => {
+            debug!(
+                get_logger(),
+                "Unable to read manifest [{}] - [{}]",
+                path.display(),
+                why
+            );
+            IndexManifest::default()
+        }
+    }
+}
+
+fn store_manifest(path: &Path, manifest: &IndexManifest) -> std::io::Result<()> {
+    let content = serde_json::to_string(manifest)?;
+    fs::write(path, content)
+}
 
+fn get_modified(metadata: &fs::Metadata) -> (u64, u32) {
+    match metadata
+        .modified()
+        .map(|time| time.duration_since(UNIX_EPOCH))
+    {
+        Ok(Ok(duration)) => (duration.as_secs(), duration.subsec_nanos()),
+        _ => (0, 0),
+    }
+}
+
+fn get_hash(adr: &Adr) -> String {
+    let mut hasher = DefaultHasher::new();
+    adr.qualified_id().hash(&mut hasher);
+    adr.content.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Builds the index of the ADRs, or updates it if it already exists: only the ADRs which have been added, changed
+/// (cf. the manifest of the index) or removed since the last build are indexed. The content of each ADR is read when
+/// it is indexed (cf. `Adr::load_content`), so that the ADRs can be listed without it (cf. `adr_repo::scan_adr`).
+///
+/// # Arguments
+///
+/// * `index_path` - the directory of the index, which must exist
+/// * `adrs` - all the ADRs to index
+///
+pub fn build_index<I>(index_path: String, adrs: I) -> tantivy::Result<IndexChanges>
+where
+    I: IntoIterator<Item = Adr>,
+{
+    info!(get_logger(), "Building Index in folder [{}]", index_path);
+
+    let now = Instant::now();
+    let index_path = Path::new(&index_path);
+    let manifest_path = get_manifest_path(index_path);
+
+    let schema = get_schema();
     let mmap_directory = MmapDirectory::open(index_path)?;
-    let index = Index::create(mmap_directory, schema.clone())?; // should use open_or_create to not overwrite existing index.
+    let (index, mut manifest) = match Index::exists(&mmap_directory) {
+        true => {
+            let index = Index::open(mmap_directory.clone())?;
+            match index.schema() == schema {
+                true => (index, load_manifest(&manifest_path)),
+                false => {
+                    info!(
+                        get_logger(),
+                        "Index [{}] has been built by a previous version - rebuilding it",
+                        index_path.display()
+                    );
+                    (
+                        Index::create(mmap_directory, schema.clone())?,
+                        IndexManifest::default(),
+                    )
+                }
+            }
+        }
+        false => (
+            Index::create(mmap_directory, schema.clone())?,
+            IndexManifest::default(),
+        ),
+    };
+    manifest.version = MANIFEST_VERSION;
     let mut index_writer = index.writer(100_000_000)?;
 
     let title = schema.get_field("title").unwrap();
     let body = schema.get_field("body").unwrap();
     let tags = schema.get_field("tags").unwrap();
     let path = schema.get_field("path").unwrap();
+    let id = schema.get_field("id").unwrap();
+    let key = schema.get_field("key").unwrap();
+
+    let mut changes = IndexChanges::default();
+    let mut keys = BTreeSet::new();
+    for mut adr in adrs {
+        let adr_key = adr.path();
+        keys.insert(adr_key.clone());
+        let previous = manifest.entries.get(&adr_key).cloned();
+
+        //an unchanged file is not read
+        let metadata = fs::metadata(&adr_key).ok();
+        let (modified_secs, modified_nanos) = metadata.as_ref().map(get_modified).unwrap_or((0, 0));
+        let size = metadata
+            .as_ref()
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        if let Some(previous) = &previous {
+            if metadata.is_some()
+                && previous.modified_secs == modified_secs
+                && previous.modified_nanos == modified_nanos
+                && previous.size == size
+            {
+                changes.unchanged += 1;
+                continue;
+            }
+        }
+
+        if let Err(why) = adr.load_content() {
+            warn!(get_logger(), "Unable to index [{}] - [{}]", adr.path(), why);
+            continue;
+        }
+        let indexed = IndexedAdr {
+            modified_secs,
+            modified_nanos,
+            size,
+            hash: get_hash(&adr),
+        };
+        let has_changed = match &previous {
+            Some(previous) => previous.hash != indexed.hash,
+            None => true,
+        };
+        manifest.entries.insert(adr_key.clone(), indexed);
+        if !has_changed {
+            //only touched
+            changes.unchanged += 1;
+            continue;
+        }
 
-    for adr in adrs {
+        debug!(get_logger(), "Indexing [{}]", adr_key);
+        index_writer.delete_term(Term::from_field_text(key, &adr_key));
         index_writer.add_document(doc!(
         title => String::from(adr.title.as_str()),
         body => String::from(adr.content.as_str()),
         tags => String::from(adr.tags.as_str()), //recreate a string from the tags Vec via Debug...
         path => String::from(adr.path().as_str()),
+        id => adr.qualified_id(), //e.g. platform:0012 in the index spanning the workspaces
+        key => adr_key,
         ));
+        match previous {
+            Some(_previous) => changes.updated += 1,
+            None => changes.added += 1,
+        }
     }
 
-    index_writer.commit()?;
+    //the ADRs which have been removed
+    let removed: Vec<String> = manifest
+        .entries
+        .keys()
+        .filter(|adr_key| !keys.contains(*adr_key))
+        .cloned()
+        .collect();
+    for adr_key in removed {
+        debug!(get_logger(), "Removing [{}] from index", adr_key);
+        index_writer.delete_term(Term::from_field_text(key, &adr_key));
+        manifest.entries.remove(&adr_key);
+        changes.removed += 1;
+    }
+
+    if !changes.is_empty() {
+        index_writer.commit()?;
+    }
+    store_manifest(&manifest_path, &manifest)?;
 
     info!(
         get_logger(),
-        "Indexing Time [{}] milli seconds",
-        now.elapsed().as_millis()
+        "Indexing Time [{}] milli seconds - [{}] added, [{}] updated, [{}] removed, [{}] unchanged",
+        now.elapsed().as_millis(),
+        changes.added,
+        changes.updated,
+        changes.removed,
+        changes.unchanged
     );
 
-    Ok(())
+    Ok(changes)
+}
+
+/// Returns true if the index has to be built or updated, i.e. it does not exist, it has been built by a previous
+/// version, or some ADRs have been added, changed or removed since it has been built (cf. its manifest). The ADRs are
+/// not read, only the metadata of their files.
+pub fn is_stale(index_path: &Path, adrs: &[Adr]) -> bool {
+    let index = match MmapDirectory::open(index_path) {
+        Ok(mmap_directory) if Index::exists(&mmap_directory) => Index::open(mmap_directory),
+        _ => return true,
+    };
+    match index {
+        Ok(index) if index.schema() == get_schema() => (),
+        _ => return true,
+    };
+
+    let manifest = load_manifest(&get_manifest_path(index_path));
+    if manifest.entries.len() != adrs.len() {
+        return true;
+    }
+    adrs.iter().any(|adr| {
+        let adr_key = adr.path();
+        match (manifest.entries.get(&adr_key), fs::metadata(&adr_key)) {
+            (Some(indexed), Ok(metadata)) => {
+                (indexed.modified_secs, indexed.modified_nanos) != get_modified(&metadata)
+                    || indexed.size != metadata.len()
+            }
+            _ => true,
+        }
+    })
+}
+
+/// Builds or updates the index if it is stale (cf. `is_stale`). Returns the changes of the index, if it has been
+/// refreshed.
+///
+/// # Arguments
+///
+/// * `index_path` - the directory of the index, created if it does not exist
+/// * `adrs` - all the ADRs to index
+///
+pub fn refresh_index(index_path: String, adrs: Vec<Adr>) -> tantivy::Result<Option<IndexChanges>> {
+    if !is_stale(Path::new(&index_path), &adrs) {
+        debug!(get_logger(), "Index [{}] is up to date", index_path);
+        return Ok(None);
+    }
+
+    fs::create_dir_all(&index_path)?;
+    build_index(index_path, adrs).map(Some)
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -80,31 +327,82 @@ pub struct SearchResult {
     pub title: [String; 1],
     pub tags: [String; 1],
     pub path: [String; 1],
+    /// The ID of the ADR, qualified by its workspace if any (empty for the indexes built before it was indexed)
+    #[serde(default)]
+    pub id: [String; 1],
 }
 
-pub fn search(index_path: String, query_as_string: String) -> tantivy::Result<Vec<SearchResult>> /*Result<()>*/
-{
+/// Returns the query matching the ADRs tagged with `tag` (or one of its descendants, e.g. `security/authn` for
+/// `security`): a term query on the `tags` field, or a phrase query if the tag is made of several tokens. Returns
+/// None if the tag has no token.
+fn get_tag_query(index: &Index, tags: Field, tag: &str) -> tantivy::Result<Option<Box<dyn Query>>> {
+    let mut terms = Vec::new();
+    index
+        .tokenizer_for_field(tags)?
+        .token_stream(tag)
+        .process(&mut |token| terms.push(Term::from_field_text(tags, &token.text)));
+
+    Ok(match terms.len() {
+        0 => None,
+        1 => Some(Box::new(TermQuery::new(
+            terms.remove(0),
+            IndexRecordOption::Basic,
+        ))),
+        _ => Some(Box::new(PhraseQuery::new(terms))),
+    })
+}
+
+/// Searches the ADRs matching `query_as_string` (cf. tantivy `QueryParser`) in their title and body, and, if `tag`
+/// is set, tagged with `tag` or one of its descendants (cf. `adr_core::adr_repo::tag_matches`). Returns the 20 best results.
+///
+/// The index is `AdrToolConfig.adr_search_index`, or `AdrToolConfig.federated_search_index` if `all_workspaces`. It is
+/// refreshed first if `AdrToolConfig.search_auto_refresh` is set (cf. `refresh_index`), the changes of the index being
+/// returned along with the results if it was out of date. Returns an error if the index does not exist (cf.
+/// `build_index`) or cannot be refreshed.
+pub fn search(
+    query_as_string: String,
+    tag: Option<&str>,
+    all_workspaces: bool,
+) -> tantivy::Result<(Vec<SearchResult>, Option<IndexChanges>)> {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    let index_path = match all_workspaces {
+        true => cfg.federated_search_index,
+        false => cfg.adr_search_index,
+    };
     debug!(
         get_logger(),
-        "Searching [{}] based on Index in folder [{}]", query_as_string, index_path
+        "Searching [{}] (tag [{:?}]) based on Index in folder [{}]",
+        query_as_string,
+        tag,
+        index_path
     );
 
+    let changes = match cfg.search_auto_refresh {
+        true => {
+            let adrs = match all_workspaces {
+                true => adr_core::adr_federation::list_all_federated_adr(&cfg.workspaces)?,
+                false => scan_adr(Path::new(&cfg.adr_src_dir))?,
+            };
+            refresh_index(index_path.clone(), adrs)?
+        }
+        false => None,
+    };
+
     let index_path = Path::new(&index_path);
     let mmap_directory = MmapDirectory::open(index_path)?;
-    //println!("file exist {}", Index::exists(&mmap_directory) );
+    if !Index::exists(&mmap_directory) {
+        return Err(tantivy::TantivyError::PathDoesNotExist(
+            index_path.to_path_buf(),
+        ));
+    }
     let index = Index::open(mmap_directory)?;
 
     //
-    let mut schema_builder = Schema::builder();
-    schema_builder.add_text_field("title", TEXT | STORED);
-    schema_builder.add_text_field("body", TEXT);
-    schema_builder.add_text_field("tags", TEXT | STORED);
-    schema_builder.add_text_field("path", TEXT | STORED);
-    let schema = schema_builder.build();
+    let schema = get_schema();
 
     let title = schema.get_field("title").unwrap();
     let body = schema.get_field("body").unwrap();
-    //let tags = schema.get_field("tags").unwrap();
+    let tags = schema.get_field("tags").unwrap();
     //let path = schema.get_field("path").unwrap();
 
     //
@@ -116,7 +414,18 @@ pub fn search(index_path: String, query_as_string: String) -> tantivy::Result<Ve
     let searcher = reader.searcher();
 
     let query_parser = QueryParser::for_index(&index, vec![title, body]);
-    let query = query_parser.parse_query(&query_as_string)?;
+    let mut query = query_parser.parse_query(&query_as_string)?;
+    if let Some(tag) = tag {
+        //the tags are tokenized, so the query can also match e.g. `network/security` for `security`: the results are checked below
+        let tag_query = match get_tag_query(&index, tags, tag)? {
+            Some(tag_query) => tag_query,
+            None => return Ok((vec![], changes)),
+        };
+        query = Box::new(BooleanQuery::from(vec![
+            (Occur::Must, query),
+            (Occur::Must, tag_query),
+        ]));
+    }
 
     let top_docs = searcher.search(&query, &TopDocs::with_limit(20))?;
 
@@ -131,8 +440,132 @@ pub fn search(index_path: String, query_as_string: String) -> tantivy::Result<Ve
 
         let doc_as_json = schema.to_json(&retrieved_doc);
         let search_result: SearchResult = serde_json::from_str(&doc_as_json).unwrap();
+        if let Some(tag) = tag {
+            //indexed tags look like "#tag1 #tag2 "
+            if !search_result.tags[0]
+                .split('#')
+                .any(|indexed_tag| tag_matches(indexed_tag, tag))
+            {
+                continue;
+            }
+        }
         results.push(search_result);
     }
 
-    Ok(results)
+    Ok((results, changes))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::search::*;
+    use std::time::Duration;
+    use tempdir::TempDir;
+
+    fn write_adr(src: &Path, name: &str, title: &str) -> Adr {
+        let content = format!("= {}\n\n*Status:* {{wip}} *Date:* 2020-01-01\n", title);
+        fs::write(src.join(name), &content).unwrap();
+        Adr::from(format!("{}", src.display()), String::from(name), content)
+    }
+
+    #[test]
+    fn test_build_index_changes() {
+        let dir = match TempDir::new("adr_search") {
+            Ok(dir) => dir,
+            Err(why) => {
+                println!("{:?}", why);
+                panic!("{}", why);
+            }
+        };
+        let src = dir.path().join("src");
+        let index = dir.path().join("index");
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&index).unwrap();
+        let index_path = format!("{}", index.display());
+
+        let first = write_adr(&src, "0001-use-kafka.adoc", "Use Kafka");
+        let second = write_adr(&src, "0002-use-rust.adoc", "Use Rust");
+        assert!(is_stale(&index, &[first.clone(), second.clone()]));
+        let changes = build_index(index_path.clone(), vec![first.clone(), second.clone()]).unwrap();
+        assert_eq!(2, changes.added);
+        assert_eq!(0, changes.unchanged);
+        assert!(!is_stale(&index, &[first.clone(), second.clone()]));
+
+        //a touched file is read again, but not indexed again as long as its content has not changed
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .open(src.join("0001-use-kafka.adoc"))
+            .unwrap();
+        file.set_modified(UNIX_EPOCH + Duration::from_secs(1_000_000))
+            .unwrap();
+        assert!(is_stale(&index, &[first.clone(), second.clone()]));
+        let hash = load_manifest(&get_manifest_path(&index)).entries[&first.path()]
+            .hash
+            .clone();
+        let changes = build_index(index_path.clone(), vec![first.clone(), second.clone()]).unwrap();
+        assert_eq!(
+            IndexChanges {
+                unchanged: 2,
+                ..IndexChanges::default()
+            },
+            changes
+        );
+        let manifest = load_manifest(&get_manifest_path(&index));
+        assert_eq!(hash, manifest.entries[&first.path()].hash);
+        assert_eq!(1_000_000, manifest.entries[&first.path()].modified_secs);
+        assert!(!is_stale(&index, &[first.clone(), second.clone()]));
+
+        //an updated file is indexed again, a removed one is removed from the index
+        let first = write_adr(&src, "0001-use-kafka.adoc", "Use Kafka Streams");
+        fs::remove_file(src.join("0002-use-rust.adoc")).unwrap();
+        assert!(is_stale(&index, std::slice::from_ref(&first)));
+        let changes = build_index(index_path.clone(), vec![first.clone()]).unwrap();
+        assert_eq!(
+            IndexChanges {
+                updated: 1,
+                removed: 1,
+                ..IndexChanges::default()
+            },
+            changes
+        );
+        let manifest = load_manifest(&get_manifest_path(&index));
+        assert_eq!(1, manifest.entries.len());
+        assert_ne!(hash, manifest.entries[&first.path()].hash);
+        assert!(!is_stale(&index, &[first]));
+    }
+
+    #[test]
+    fn test_build_index_rebuild() {
+        let dir = match TempDir::new("adr_search") {
+            Ok(dir) => dir,
+            Err(why) => {
+                println!("{:?}", why);
+                panic!("{}", why);
+            }
+        };
+        let src = dir.path().join("src");
+        let index = dir.path().join("index");
+        fs::create_dir_all(&src).unwrap();
+        fs::create_dir_all(&index).unwrap();
+        let index_path = format!("{}", index.display());
+        let adrs = vec![write_adr(&src, "0001-use-kafka.adoc", "Use Kafka")];
+
+        //an index with another schema is built again
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT | STORED);
+        Index::create(MmapDirectory::open(&index).unwrap(), schema_builder.build()).unwrap();
+        assert!(is_stale(&index, &adrs));
+        let changes = build_index(index_path.clone(), adrs.clone()).unwrap();
+        assert_eq!(1, changes.added);
+        assert!(!is_stale(&index, &adrs));
+
+        //as well as an index with a manifest of another version
+        let manifest_path = get_manifest_path(&index);
+        let mut manifest = load_manifest(&manifest_path);
+        manifest.version = MANIFEST_VERSION + 1;
+        store_manifest(&manifest_path, &manifest).unwrap();
+        assert!(is_stale(&index, &adrs));
+        let changes = build_index(index_path, adrs.clone()).unwrap();
+        assert_eq!(1, changes.added);
+        assert!(!is_stale(&index, &adrs));
+    }
 }
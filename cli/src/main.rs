@@ -1,9 +1,8 @@
 extern crate slog;
-extern crate slog_term;
 use slog::*;
 
 use std::io::{self};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[macro_use]
 extern crate prettytable;
@@ -13,51 +12,62 @@ use prettytable::{Cell, Row, Table};
 extern crate lazy_static;
 
 extern crate clap;
-use clap::{App, AppSettings, Arg, SubCommand};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 
 extern crate dirs;
 
+extern crate chrono;
+use chrono::{NaiveDate, Utc};
+
 extern crate adr_core;
-use adr_core::adr_repo::Status;
+use adr_core::adr_changelog::ChangelogFormat;
+use adr_core::adr_due::DueReason;
+use adr_core::adr_filter::{AdrFilter, SortKey};
+use adr_core::adr_lint::LintLevel;
+use adr_core::adr_repo::{Adr, ReviewAction, Status, TransitionStatus};
 extern crate adr_config;
 use adr_config::config::AdrToolConfig;
+use adr_config::logger::{get_verbosity, LogFormat, LogSettings};
 extern crate adr_search;
 
 fn get_logger() -> slog::Logger {
-    let cfg: AdrToolConfig = adr_config::config::get_config();
-
-    let decorator = slog_term::TermDecorator::new().build();
-    let drain = slog_term::FullFormat::new(decorator).build().fuse();
-    let drain = slog_async::Async::new(drain).build().fuse();
-    let drain = slog::LevelFilter::new(
-        drain,
-        Level::from_usize(cfg.log_level).unwrap_or(Level::Debug),
-    )
-    .fuse();
-
-    slog::Logger::root(drain, o!())
+    adr_config::logger::get_logger()
 }
 
-pub fn list_all_adr() -> io::Result<()> {
+/// Lists the Decision Records, as they are in the working directory or as they were at the git revision `rev`
+pub fn list_all_adr(filter: &AdrFilter, rev: Option<&str>, all_workspaces: bool) -> io::Result<()> {
     let cfg: AdrToolConfig = adr_config::config::get_config();
 
     let mut table = Table::new();
     table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
     table.set_titles(
-        row![b -> "ID", b -> "Title", b -> "Date", b-> "Status", b -> "File", b -> "Tags"],
+        row![b -> "ID", b -> "Title", b -> "Created", b -> "Date", b-> "Status", b -> "File", b -> "Tags"],
     );
-    for entry in adr_core::adr_repo::list_all_adr(Path::new(&cfg.adr_src_dir))? {
+    let adrs = match (rev, all_workspaces) {
+        (_, true) => adr_core::adr_federation::list_all_federated_adr(&cfg.workspaces)?,
+        (Some(rev), false) => {
+            adr_core::adr_git::list_all_adr_at_rev(Path::new(&cfg.adr_src_dir), rev)?
+        }
+        (None, false) => adr_core::adr_repo::scan_adr(Path::new(&cfg.adr_src_dir))?,
+    };
+    let mut adrs = adr_core::adr_filter::filter_adr(adrs, filter);
+    if rev.is_none() {
+        adr_core::adr_git::load_git_history(&mut adrs)?;
+    }
+    for entry in adrs {
         //table.add_row(row![entry.title, Fg->entry.status, entry.path, entry.tags]);
         let style = match entry.status {
             Status::WIP => "Fy",
+            Status::REVIEW => "Fy",
             Status::DECIDED => "Fg",
             Status::COMPLETED => "Fg",
             Status::COMPLETES => "Fg",
             _ => "Fr",
         };
         table.add_row(Row::new(vec![
-            Cell::new(&entry.file_id.to_string()),
+            Cell::new(&entry.qualified_id()),
             Cell::new(&entry.title),
+            Cell::new(&entry.created_at().unwrap_or_default()),
             Cell::new(&entry.date),
             Cell::new(&entry.status.as_str()).style_spec(style),
             Cell::new(&entry.path()),
@@ -71,50 +81,425 @@ pub fn list_all_adr() -> io::Result<()> {
     Ok(())
 }
 
-fn set_config(name: &str, value: &str) -> Result<()> {
+/// The arguments to filter the Decision Records, cf. `build_filter`
+fn filter_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("status")
+            .short("s")
+            .long("status")
+            .takes_value(true)
+            .possible_values(&[
+                "wip",
+                "review",
+                "decided",
+                "completed",
+                "completes",
+                "superseded",
+                "supersedes",
+                "obsoleted",
+            ])
+            .help("Only keep the Decision Records with this status"),
+        Arg::with_name("tag")
+            .short("t")
+            .long("tag")
+            .takes_value(true)
+            .help("Only keep the Decision Records tagged with this tag (or one of its children)"),
+        Arg::with_name("from")
+            .long("from")
+            .takes_value(true)
+            .help("Only keep the Decision Records dated from this date (YYYY-MM-DD)"),
+        Arg::with_name("to")
+            .long("to")
+            .takes_value(true)
+            .help("Only keep the Decision Records dated until this date (YYYY-MM-DD)"),
+        Arg::with_name("path")
+            .short("p")
+            .long("path")
+            .takes_value(true)
+            .help("Only keep the Decision Records of this sub-directory"),
+        Arg::with_name("decider")
+            .short("d")
+            .long("decider")
+            .takes_value(true)
+            .help("Only keep the Decision Records decided by this person"),
+        Arg::with_name("where")
+            .short("w")
+            .long("where")
+            .takes_value(true)
+            .help("Filter with an expression, e.g. \"status=decided and (tag=security or date>=2020-01-01)\""),
+    ]
+}
+
+fn build_filter(matches: &ArgMatches) -> std::result::Result<AdrFilter, String> {
+    let parse_date = |name: &str| -> std::result::Result<Option<NaiveDate>, String> {
+        match matches.value_of(name) {
+            Some(val) => match adr_core::adr_filter::parse_date(val) {
+                Some(date) => Ok(Some(date)),
+                None => Err(format!("Invalid date [{}] - expected YYYY-MM-DD", val)),
+            },
+            None => Ok(None),
+        }
+    };
+
+    Ok(AdrFilter {
+        status: matches
+            .value_of("status")
+            .map(|val| Status::from_str(val.to_string())),
+        tag: matches.value_of("tag").map(String::from),
+        from_date: parse_date("from")?,
+        to_date: parse_date("to")?,
+        path: matches.value_of("path").map(String::from),
+        decider: matches.value_of("decider").map(String::from),
+        expression: match matches.value_of("where") {
+            Some(val) => Some(adr_core::adr_filter::parse_expression(val)?),
+            None => None,
+        },
+        sort: matches
+            .value_of("sort")
+            .unwrap_or("id")
+            .parse::<SortKey>()?,
+        reverse: matches.is_present("reverse"),
+        limit: match matches.value_of("limit") {
+            Some(val) => Some(
+                val.parse()
+                    .map_err(|_| format!("Invalid limit [{}]", val))?,
+            ),
+            None => None,
+        },
+    })
+}
+
+fn stats(filter: &AdrFilter, format: &str) -> io::Result<()> {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+
+    let adrs = adr_core::adr_repo::scan_adr(Path::new(&cfg.adr_src_dir))?;
+    let mut adrs = adr_core::adr_filter::filter_adr(adrs, filter);
+    adr_core::adr_git::load_git_history(&mut adrs)?;
+    let stats = adr_core::adr_stats::get_stats(&adrs);
+
+    if format == "json" {
+        println!("{}", stats.to_json());
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row![b -> "Metric", b -> "Value"]);
+    table.add_row(row!["Decision Records", stats.total]);
+    table.add_row(row![
+        "Median days from wip to decided",
+        match stats.median_days_to_decided {
+            Some(days) => format!("{:.1}", days),
+            None => String::from("n/a"),
+        }
+    ]);
+    table.add_row(row![
+        "Supersede rate",
+        match stats.supersede_rate {
+            Some(rate) => format!("{:.1}%", rate * 100.0),
+            None => String::from("n/a"),
+        }
+    ]);
+    table.printstd();
+
+    let print_counts = |title: &str, counts: &std::collections::BTreeMap<String, usize>| {
+        let mut table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+        table.set_titles(row![b -> title, b -> "Count"]);
+        for (key, val) in counts.iter() {
+            table.add_row(row![key, val]);
+        }
+        println!();
+        table.printstd();
+    };
+    print_counts("Status", &stats.by_status);
+    print_counts("Tag", &stats.by_tag);
+    print_counts("Directory", &stats.by_directory);
+    print_counts("Decided in", &stats.decisions_per_month);
+
+    Ok(())
+}
+
+/// Shows the details of a Decision Record, with the dates of its git history (if any)
+fn show(file_path: &str, rev: Option<&str>) -> io::Result<()> {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    let base_path = Path::new(&cfg.adr_src_dir);
+    let mut adrs = match rev {
+        Some(rev) => vec![adr_core::adr_git::build_adr_at_rev(
+            base_path,
+            Path::new(file_path),
+            rev,
+        )?],
+        None => vec![adr_core::adr_repo::build_adr(
+            base_path,
+            Path::new(file_path),
+        )?],
+    };
+    if rev.is_none() {
+        adr_core::adr_git::load_git_history(&mut adrs)?;
+    }
+    let adr = &adrs[0];
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.add_row(row![b -> "ID", adr.file_id]);
+    table.add_row(row![b -> "Title", adr.title]);
+    table.add_row(row![b -> "Status", adr.status.as_str()]);
+    table.add_row(row![b -> "Date", adr.date]);
+    table.add_row(row![b -> "Created", adr.created_at().unwrap_or_default()]);
+    if let Some(git_history) = &adr.git_history {
+        table.add_row(row![b -> "Last modified", git_history.last_modified]);
+        table.add_row(row![b -> "Authors", git_history.authors.join(", ")]);
+    }
+    table.add_row(row![b -> "Decision Makers", adr.deciders.join(", ")]);
+    table.add_row(row![b -> "Tags", adr.tags]);
+    if let Some(revisit) = &adr.revisit {
+        table.add_row(row![b -> "Revisit", revisit]);
+    }
+    let references = adr.references();
+    if !references.is_empty() {
+        table.add_row(row![b -> "References", describe_references(&cfg, &references)]);
+    }
+    table.add_row(row![b -> "File", adr.path()]);
+    table.printstd();
+
+    //the status the ADR went through, from its recorded history and its git history
+    let committed = match &adr.git_history {
+        Some(git_history) => git_history.status_dates.clone(),
+        None => Vec::new(),
+    };
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row![b -> "Status", b -> "Since"]);
+    let mut statuses: Vec<Status> = Vec::new();
+    for transition in adr.history.iter().chain(committed.iter()) {
+        if !statuses.contains(&transition.status) {
+            statuses.push(transition.status);
+            table.add_row(row![
+                transition.status.as_str(),
+                adr.status_date(transition.status).unwrap_or_default()
+            ]);
+        }
+    }
+    println!();
+    table.printstd();
+
+    Ok(())
+}
+
+/// Returns the references of a Decision Record, with the title of the ones to the other workspaces (e.g.
+/// `platform:0012 (Use Kafka)`)
+fn describe_references(cfg: &AdrToolConfig, references: &[String]) -> String {
+    let federated = match references
+        .iter()
+        .any(|reference| adr_core::adr_federation::parse_qualified_id(reference).is_some())
+    {
+        true => match adr_core::adr_federation::list_all_federated_adr(&cfg.workspaces) {
+            Ok(federated) => Some(federated),
+            Err(why) => {
+                eprintln!(
+                    "Unable to resolve the references to the workspaces - {}",
+                    why
+                );
+                None
+            }
+        },
+        false => Some(Vec::new()),
+    };
+    let from = Adr::default();
+
+    references
+        .iter()
+        .map(
+            |reference| match adr_core::adr_federation::parse_qualified_id(reference) {
+                Some(_) => match &federated {
+                    Some(federated) => {
+                        match adr_core::adr_federation::resolve_reference(
+                            federated, &from, reference,
+                        ) {
+                            Some(adr) => format!("{} ({})", reference, adr.title),
+                            None => format!("{} (not found)", reference),
+                        }
+                    }
+                    None => reference.clone(),
+                },
+                None => reference.clone(),
+            },
+        )
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Prints the changes of the Decision Records between two git revisions, given as `from..to` (`to` is `HEAD` if omitted)
+fn changelog(range: &str, format: &str) -> io::Result<()> {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    let base_path = Path::new(&cfg.adr_src_dir);
+    let format = match format.parse::<ChangelogFormat>() {
+        Ok(format) => format,
+        Err(why) => return Err(io::Error::new(io::ErrorKind::InvalidInput, why)),
+    };
+    let (from, to) = match range.find("..") {
+        Some(position) => (&range[..position], &range[position + 2..]),
+        None => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid range [{}] - expected <from>..<to>", range),
+            ))
+        }
+    };
+    let to = match to.is_empty() {
+        true => "HEAD",
+        false => to,
+    };
+
+    let entries = adr_core::adr_changelog::get_changelog(
+        adr_core::adr_git::list_all_adr_at_rev(base_path, from)?,
+        adr_core::adr_git::list_all_adr_at_rev(base_path, to)?,
+    );
+    print!(
+        "{}",
+        adr_core::adr_changelog::render_changelog(&entries, from, to, format)
+    );
+
+    Ok(())
+}
+
+/// Sets the property of the global configuration, checking first that the directory exists (or creating it) when
+/// the property is a path
+fn set_config(name: &str, value: &str, create: bool) -> io::Result<()> {
+    let is_path = match adr_config::config::get_property(name) {
+        Ok(property) => property.property_type == adr_config::config::PropertyType::PATH,
+        Err(_why) => name == adr_config::config::ADR_ROOT_DIR,
+    };
+    if is_path && !value.trim().is_empty() {
+        adr_config::config::check_dir(Path::new(value), create)?;
+    }
     adr_config::config::set_config(name, value)
 }
 
+fn get_config(name: &str) -> std::result::Result<(), String> {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    println!("{}", adr_config::config::get_property_value(&cfg, name)?);
+
+    Ok(())
+}
+
+/// Opens the global configuration file in `$VISUAL` or `$EDITOR` (`vi` by default) and checks it once saved
+fn edit_config() -> io::Result<()> {
+    let path = match adr_config::config::get_config_path("adrust-tools") {
+        Some(path) => path,
+        None => {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Unable to find the configuration directory",
+            ))
+        }
+    };
+    if !path.exists() {
+        adr_config::config::store_config("adrust-tools", &AdrToolConfig::default())?;
+    }
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| String::from("vi"));
+    let status = std::process::Command::new(&editor).arg(&path).status()?;
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "[{}] exited with [{}]",
+            editor, status
+        )));
+    }
+
+    match adr_config::config::load_config_from_name("adrust-tools") {
+        Ok(_) => Ok(()),
+        Err(why) => Err(io::Error::new(io::ErrorKind::InvalidData, why)),
+    }
+}
+
 /**
- * default config will be stored in directories::ProjectDir::config_dir() (a.k.a ls -la $HOME/Library/Preferences/)
+ * default config will be stored in directories::ProjectDir::config_dir() (a.k.a ls -la $HOME/.config/adrust-tools/)
  *
- * TODO need to find a proper way to map to the config struct - could be managed with a macro
+ * the properties are the ones of the registry, cf. adr_config::config::CONFIG_PROPERTIES
  */
 fn list_all_config() -> Result<()> {
     info!(get_logger(), "list all configuration elements",);
+    let layers = adr_config::config::get_config_layers();
+    let cfg: AdrToolConfig = layers.config;
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(
+        row![b -> "Property", b -> "Value", b -> "Type", b -> "Modifiable", b -> "Source", b -> "Description"],
+    );
+    for property in adr_config::config::CONFIG_PROPERTIES.iter() {
+        let value = adr_config::config::get_property_value(&cfg, property.name).unwrap_or_default();
+        let modifiable = match property.is_modifiable() {
+            true => "Y",
+            false => "N",
+        };
+        let source = match layers.sources.get(property.name) {
+            Some(source) => source.as_str(),
+            None => "",
+        };
+        table.add_row(row![
+            property.name,
+            value,
+            property.property_type.as_str(),
+            modifiable,
+            source,
+            property.description
+        ]);
+    }
+
+    // Print the table to stdout
+    table.printstd();
+
+    if let Some(path) = layers.local_config {
+        println!("\nLocal configuration [{}]", path.display());
+    }
+    Ok(())
+}
+
+fn add_workspace(matches: &ArgMatches) -> io::Result<()> {
+    let dir = Path::new(matches.value_of("dir").unwrap());
+    adr_config::config::check_dir(dir, matches.is_present("create"))?;
+
+    let workspace = adr_config::config::WorkspaceDefinition {
+        name: String::from(matches.value_of("name").unwrap()),
+        adr_src_dir: format!("{}", dir.display()),
+        adr_template_dir: match matches.value_of("template-dir") {
+            Some(template_dir) => String::from(template_dir),
+            None => format!("{}", dir.join("templates").display()),
+        },
+        adr_search_index: match matches.value_of("search-index") {
+            Some(search_index) => String::from(search_index),
+            None => format!("{}", dir.join(".index").display()),
+        },
+    };
+    adr_config::config::add_workspace(workspace)
+}
+
+fn list_all_workspaces() -> Result<()> {
     let cfg: AdrToolConfig = adr_config::config::get_config();
 
     let mut table = Table::new();
     table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
-    table.set_titles(row![b -> "Property", b -> "Value", b -> "Modifiable"]);
-    //table.add_row(row![adr_config::config::ADR_ROOT_DIR, cfg.adr_root_dir, "Y"]);
-    table.add_row(row![adr_config::config::ADR_SRC_DIR, cfg.adr_src_dir, "Y"]);
-    table.add_row(row![
-        adr_config::config::ADR_TEMPLATE_DIR,
-        cfg.adr_template_dir,
-        "Y"
-    ]);
-    table.add_row(row![
-        adr_config::config::ADR_TEMPLATE_FILE,
-        cfg.adr_template_file,
-        "Y"
-    ]);
-    table.add_row(row![
-        adr_config::config::ADR_SEARCH_INDEX,
-        cfg.adr_search_index,
-        "N"
-    ]);
-    table.add_row(row![adr_config::config::LOG_LEVEL, cfg.log_level, "Y"]);
-    table.add_row(row![
-        adr_config::config::USE_ID_PREFIX,
-        cfg.use_id_prefix,
-        "Y"
-    ]);
-    table.add_row(row![
-        adr_config::config::ID_PREFIX_WIDTH,
-        cfg.id_prefix_width,
-        "Y"
-    ]);
+    table.set_titles(
+        row![b -> "", b -> "Name", b -> "Decision Records", b -> "Templates", b -> "Search Index"],
+    );
+    for workspace in cfg.workspaces.iter() {
+        let current = match workspace.name == cfg.workspace {
+            true => "*",
+            false => "",
+        };
+        table.add_row(row![
+            current,
+            workspace.name,
+            workspace.adr_src_dir,
+            workspace.adr_template_dir,
+            workspace.adr_search_index
+        ]);
+    }
 
     // Print the table to stdout
     table.printstd();
@@ -122,17 +507,38 @@ fn list_all_config() -> Result<()> {
     Ok(())
 }
 
+/// Returns the value of a global argument, which may be given before or after the subcommands
+fn global_value_of<'a>(matches: &'a ArgMatches, name: &str) -> Option<&'a str> {
+    match matches.subcommand() {
+        (_, Some(sub_matches)) => {
+            global_value_of(sub_matches, name).or_else(|| matches.value_of(name))
+        }
+        _ => matches.value_of(name),
+    }
+}
+
 fn list_all_tags() -> Result<()> {
     let cfg: AdrToolConfig = adr_config::config::get_config();
 
     let mut table = Table::new();
     table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
-    table.set_titles(row![b -> "Tags", b -> "Popularity"]);
+    table.set_titles(row![b -> "Tags", b -> "Popularity", b -> "Description"]);
 
     let popularity = adr_core::adr_repo::get_tags_popularity(Path::new(&cfg.adr_src_dir))?;
 
     for (key, val) in popularity.iter() {
-        table.add_row(row![key, val]);
+        let description = match cfg.allowed_tags.iter().find(|def| def.name == key.trim()) {
+            Some(def) => def.description.as_str(),
+            None => "",
+        };
+        table.add_row(row![key, val, description]);
+    }
+
+    //tags which are defined but unused
+    for def in cfg.allowed_tags.iter() {
+        if !popularity.keys().any(|key| key.trim() == def.name) {
+            table.add_row(row![def.name, 0, def.description]);
+        }
     }
 
     // Print the table to stdout
@@ -141,31 +547,327 @@ fn list_all_tags() -> Result<()> {
     Ok(())
 }
 
-fn build_index() -> Result<()> {
+fn list_tags_tree() -> Result<()> {
     let cfg: AdrToolConfig = adr_config::config::get_config();
-    let adrs = match adr_core::adr_repo::list_all_adr(Path::new(&cfg.adr_src_dir)) {
-        Ok(e) => e,
-        Err(why) => panic!(format!("{:?}", why)),
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row![b -> "Tags", b -> "Popularity", b -> "Popularity (incl. children)"]);
+
+    let popularity = adr_core::adr_repo::get_tags_popularity(Path::new(&cfg.adr_src_dir))?;
+    let rollup = adr_core::adr_repo::get_tags_rollup(Path::new(&cfg.adr_src_dir))?;
+
+    for (key, val) in rollup.iter() {
+        let segments: Vec<&str> = key.split('/').collect();
+        let own = popularity
+            .iter()
+            .filter(|(tag, _)| tag.trim() == key)
+            .map(|(_, count)| *count)
+            .sum::<u32>();
+        table.add_row(row![
+            format!(
+                "{}{}",
+                "  ".repeat(segments.len() - 1),
+                segments[segments.len() - 1]
+            ),
+            own,
+            val
+        ]);
+    }
+
+    // Print the table to stdout
+    table.printstd();
+
+    Ok(())
+}
+
+fn print_retagged_adrs(files: Vec<String>, dry_run: bool) {
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    match dry_run {
+        true => table.set_titles(row![b -> "File (would be modified)"]),
+        false => table.set_titles(row![b -> "File (modified)"]),
     };
-    adr_search::search::build_index(cfg.adr_search_index, adrs).unwrap();
+
+    for file in files {
+        table.add_row(row![file]);
+    }
+
+    // Print the table to stdout
+    table.printstd();
+}
+
+fn add_tag(file_path: &str, tag: &str, dry_run: bool) -> io::Result<()> {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    let base_path = Path::new(&cfg.adr_src_dir);
+
+    let mut files = Vec::new();
+    if adr_core::adr_repo::add_tag(base_path, file_path, tag, &cfg.allowed_tags, dry_run)? {
+        files.push(String::from(file_path));
+    }
+    print_retagged_adrs(files, dry_run);
 
     Ok(())
 }
 
-fn search(query: String) -> Result<()> {
+fn remove_tag(file_path: &str, tag: &str, dry_run: bool) -> io::Result<()> {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    let base_path = Path::new(&cfg.adr_src_dir);
+
+    let mut files = Vec::new();
+    if adr_core::adr_repo::remove_tag(base_path, file_path, tag, dry_run)? {
+        files.push(String::from(file_path));
+    }
+    print_retagged_adrs(files, dry_run);
+
+    Ok(())
+}
+
+fn merge_tags(tags: Vec<&str>, into: &str, dry_run: bool) -> io::Result<()> {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    let base_path = Path::new(&cfg.adr_src_dir);
+
+    let files = adr_core::adr_repo::merge_tags(base_path, &tags, into, &cfg.allowed_tags, dry_run)?;
+    print_retagged_adrs(files, dry_run);
+
+    Ok(())
+}
+
+fn lint(staged: bool) -> io::Result<bool> {
     let cfg: AdrToolConfig = adr_config::config::get_config();
 
     let mut table = Table::new();
     table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
-    table.set_titles(row![b -> "Title", b -> "File", b -> "(Indexed) Tags"]);
+    table.set_titles(row![b -> "Level", b -> "File", b -> "Message"]);
+
+    let issues = match staged {
+        true => adr_core::adr_lint::lint_staged_adr(&cfg)?,
+        false => adr_core::adr_lint::lint_all_adr(&cfg)?,
+    };
+    for issue in issues.iter() {
+        let style = match issue.level {
+            LintLevel::WARNING => "Fy",
+            LintLevel::ERROR => "Fr",
+        };
+        table.add_row(Row::new(vec![
+            Cell::new(issue.level.as_str()).style_spec(style),
+            Cell::new(&issue.path),
+            Cell::new(&issue.message),
+        ]));
+    }
+
+    table.printstd();
+
+    Ok(!issues.iter().any(|issue| issue.level == LintLevel::ERROR))
+}
+
+/// Commits the Decision Records touched by a lifecycle change, if `auto_commit` is enabled (and `--no-commit` is not given)
+fn commit_adrs(matches: &ArgMatches, paths: &[&Path], message: &str) {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    if !cfg.auto_commit || matches.is_present("no-commit") {
+        return;
+    }
+
+    if let Err(why) = adr_core::adr_git::commit_files(paths, message) {
+        eprintln!("Unable to commit [{}] - {}", message, why);
+        std::process::exit(1);
+    }
+}
 
-    let results = match adr_search::search::search(cfg.adr_search_index, query) {
-        Ok(e) => e,
-        Err(why) => panic!(format!("{:?}", why)),
+/// Transitions the Decision Record `--path` by the Decision Record `--by` (e.g. `completed-by`), either of them
+/// being possibly a Decision Record of another workspace given by its qualified ID (e.g. `platform:0012`)
+fn transition_by(
+    matches: &ArgMatches,
+    transition: TransitionStatus,
+    action: &str,
+) -> io::Result<()> {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    let (from, from_path) = locate_adr(&cfg, matches.value_of("path").unwrap())?;
+    let (by, by_path) = locate_adr(&cfg, matches.value_of("by").unwrap())?;
+
+    //the Decision Records of different workspaces reference each other by their qualified ID
+    let references = match from.workspace == by.workspace {
+        true => None,
+        false if from.workspace.is_empty() || by.workspace.is_empty() => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Referencing another workspace needs a current workspace - cf. adr workspace use",
+            ))
+        }
+        false => Some((from.qualified_id(), by.qualified_id())),
     };
+    let transitioned = adr_core::adr_repo::transition_across(
+        transition,
+        Path::new(&from.base_path),
+        &from_path,
+        Path::new(&by.base_path),
+        &by_path,
+        match &references {
+            Some((from_reference, by_reference)) => Some((from_reference, by_reference)),
+            None => None,
+        },
+        cfg.review_quorum,
+    )?;
+
+    if transitioned {
+        let message = adr_core::adr_git::commit_message_by(
+            Path::new(&by_path),
+            action,
+            Path::new(&from_path),
+        );
+        match references {
+            //the workspaces may be different git repositories
+            Some(_) => {
+                commit_adrs(matches, &[Path::new(&by_path)], &message);
+                commit_adrs(matches, &[Path::new(&from_path)], &message);
+            }
+            None => commit_adrs(
+                matches,
+                &[Path::new(&by_path), Path::new(&from_path)],
+                &message,
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the Decision Record given by its path or by its qualified ID (e.g. `platform:0012`), with its path
+fn locate_adr(cfg: &AdrToolConfig, path: &str) -> io::Result<(Adr, String)> {
+    match adr_core::adr_federation::parse_qualified_id(path) {
+        Some(_) => {
+            let adr = adr_core::adr_federation::find_adr(&cfg.workspaces, path)?;
+            let path = adr.path();
+            Ok((adr, path))
+        }
+        None => {
+            let mut adr =
+                adr_core::adr_repo::build_adr(Path::new(&cfg.adr_src_dir), Path::new(path))?;
+            adr.workspace = cfg.workspace.clone();
+            Ok((adr, String::from(path)))
+        }
+    }
+}
+
+/// Lists the reviews of the Decision Record and its approvals compared to the quorum
+fn list_reviews(file_path: &str) -> io::Result<()> {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    let adr = adr_core::adr_repo::build_adr(Path::new(&cfg.adr_src_dir), Path::new(file_path))?;
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row![b -> "Date", b -> "Review", b -> "Reviewer", b -> "Note"]);
+    for review in adr.reviews.iter() {
+        let style = match review.action {
+            ReviewAction::REQUESTED => "Fy",
+            ReviewAction::APPROVED => "Fg",
+            ReviewAction::REJECTED => "Fr",
+        };
+        table.add_row(Row::new(vec![
+            Cell::new(&review.date),
+            Cell::new(review.action.as_str()).style_spec(style),
+            Cell::new(&review.reviewer),
+            Cell::new(&review.note),
+        ]));
+    }
+
+    table.printstd();
+    println!(
+        "Status [{}] - approved by [{}] - [{}] approval(s) needed",
+        adr.status.as_str(),
+        adr.approvals().join(", "),
+        cfg.review_quorum
+    );
+
+    Ok(())
+}
+
+/// Lists the Decision Records which are wip for too long or have to be revisited. Returns `false` if any.
+fn due(wip_max_age_days: Option<u32>) -> io::Result<bool> {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    let wip_max_age_days = wip_max_age_days.unwrap_or(cfg.wip_max_age_days);
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(
+        row![b -> "ID", b -> "Title", b -> "Date", b-> "Status", b -> "File", b -> "Reason"],
+    );
+
+    let adrs = adr_core::adr_repo::scan_adr(Path::new(&cfg.adr_src_dir))?;
+    let due_adrs = adr_core::adr_due::get_due_adr(adrs, wip_max_age_days, Utc::now().date_naive());
+    for entry in due_adrs.iter() {
+        let style = match entry.reason {
+            DueReason::STALE(_) => "Fy",
+            DueReason::REVISIT(_) => "Fr",
+        };
+        table.add_row(Row::new(vec![
+            Cell::new(&entry.adr.file_id.to_string()),
+            Cell::new(&entry.adr.title),
+            Cell::new(&entry.adr.date),
+            Cell::new(entry.adr.status.as_str()),
+            Cell::new(&entry.adr.path()),
+            Cell::new(&entry.reason.as_string()).style_spec(style),
+        ]));
+    }
+
+    table.printstd();
+
+    Ok(due_adrs.is_empty())
+}
+
+fn build_index(all_workspaces: bool) -> io::Result<()> {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    let (index_path, adrs) = if all_workspaces {
+        (
+            cfg.federated_search_index,
+            adr_core::adr_federation::list_all_federated_adr(&cfg.workspaces)?,
+        )
+    } else {
+        (
+            cfg.adr_search_index,
+            adr_core::adr_repo::scan_adr(Path::new(&cfg.adr_src_dir))?,
+        )
+    };
+    std::fs::create_dir_all(&index_path)?;
+    adr_search::search::build_index(index_path, adrs)
+        .map_err(|why| io::Error::other(format!("Unable to build the search index - [{}]", why)))?;
+
+    Ok(())
+}
+
+fn search(
+    query: String,
+    tag: Option<&str>,
+    all_workspaces: bool,
+) -> std::result::Result<(), String> {
+    let (results, changes) =
+        adr_search::search::search(query, tag, all_workspaces).map_err(|why| {
+            format!(
+                "Unable to search the Decision Records - {} (cf. adr search --build-index)",
+                why
+            )
+        })?;
+    if let Some(changes) = changes {
+        let cfg: AdrToolConfig = adr_config::config::get_config();
+        eprintln!(
+            "Search index [{}] was out of date - reindexed: [{}] added, [{}] updated, [{}] removed",
+            match all_workspaces {
+                true => cfg.federated_search_index,
+                false => cfg.adr_search_index,
+            },
+            changes.added,
+            changes.updated,
+            changes.removed
+        );
+    }
+
+    let mut table = Table::new();
+    table.set_format(*format::consts::FORMAT_NO_BORDER_LINE_SEPARATOR);
+    table.set_titles(row![b -> "ID", b -> "Title", b -> "File", b -> "(Indexed) Tags"]);
 
     for entry in results {
         table.add_row(Row::new(vec![
+            Cell::new(&entry.id[0]),
             Cell::new(&entry.title[0]),
             Cell::new(&entry.path[0]),
             Cell::new(&entry.tags[0]),
@@ -184,6 +886,37 @@ fn init() -> Result<()> {
     adr_config::config::init()
 }
 
+/// Registers the merge driver of the Decision Records in the git repository of the Decision Records
+fn init_git() -> io::Result<bool> {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    adr_core::adr_git::install_merge_driver(
+        Path::new(&cfg.adr_src_dir),
+        &format!("{} merge-driver %O %A %B", crate_name()),
+        &cfg.adr_extensions,
+    )
+}
+
+/// Writes the git pre-commit hook which lints the staged Decision Records
+fn install_hook(force: bool) -> io::Result<bool> {
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    adr_core::adr_git::install_pre_commit_hook(
+        Path::new(&cfg.adr_src_dir),
+        &format!("{} lint --staged", crate_name()),
+        force,
+    )
+}
+
+/// The name of the binary, as git has to run it as merge driver or hook
+fn crate_name() -> String {
+    match std::env::current_exe() {
+        Ok(path) => match path.file_stem() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => String::from("adr"),
+        },
+        Err(_why) => String::from("adr"),
+    }
+}
+
 ///
 /// The main program - start the CLI ...
 fn main() {
@@ -191,54 +924,322 @@ fn main() {
     let _options = App::new("adr")
         .version("0.1.0")
         .about("A CLI to help you manage your ADR in git")
+        .args(&[
+            Arg::with_name("config")
+                .long("config")
+                .takes_value(true)
+                .global(true)
+                .help("The local configuration file to use instead of the .adrust.toml of the current directory"),
+            Arg::with_name("src-dir")
+                .long("src-dir")
+                .takes_value(true)
+                .global(true)
+                .help("The directory of the Decision Records (overrides adr_src_dir)"),
+            Arg::with_name("log-level")
+                .long("log-level")
+                .takes_value(true)
+                .global(true)
+                .help("The log level (overrides log_level)"),
+            Arg::with_name("workspace")
+                .long("workspace")
+                .takes_value(true)
+                .global(true)
+                .help("The workspace to use instead of the current one (cf. adr workspace list)"),
+            Arg::with_name("verbose")
+                .short("v")
+                .multiple(true)
+                .global(true)
+                .conflicts_with("quiet")
+                .help("Log more (one level per occurrence, e.g. -vv)"),
+            Arg::with_name("quiet")
+                .short("q")
+                .multiple(true)
+                .global(true)
+                .help("Log less (one level per occurrence, e.g. -qq)"),
+            Arg::with_name("log-format")
+                .long("log-format")
+                .takes_value(true)
+                .possible_values(&["text", "json"])
+                .global(true)
+                .help("The format of the logs"),
+            Arg::with_name("log-file")
+                .long("log-file")
+                .takes_value(true)
+                .global(true)
+                .help("Append the logs to this file instead of the terminal"),
+        ])
         .subcommand(
             SubCommand::with_name("list")
                 .about("Lists all Decision Records")
-                .version("0.1.0"),
+                .version("0.1.0")
+                .args(&filter_args())
+                .args(&[
+                    Arg::with_name("sort")
+                        .long("sort")
+                        .takes_value(true)
+                        .possible_values(&["id", "date", "title", "status"])
+                        .default_value("id")
+                        .help("Sort the Decision Records"),
+                    Arg::with_name("reverse")
+                        .short("r")
+                        .long("reverse")
+                        .help("Reverse the order of the Decision Records"),
+                    Arg::with_name("limit")
+                        .short("l")
+                        .long("limit")
+                        .takes_value(true)
+                        .help("List at most this number of Decision Records"),
+                    Arg::with_name("rev")
+                        .long("rev")
+                        .takes_value(true)
+                        .help("Read the Decision Records as they were at this git revision (e.g. a tag)"),
+                    Arg::with_name("all-workspaces")
+                        .long("all-workspaces")
+                        .conflicts_with("rev")
+                        .help("List the Decision Records of all the workspaces, e.g. platform:0012"),
+                ]),
         )
         .subcommand(
             SubCommand::with_name("init")
                 .about("Init ADRust based on config")
-                .version("0.1.0"),
+                .version("0.1.0")
+                .arg(
+                    Arg::with_name("git")
+                        .long("git")
+                        .help("Register the merge driver of the Decision Records in their git repository"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("merge-driver")
+                .about("The git merge driver of the Decision Records (cf. init --git)")
+                .version("0.1.0")
+                .args(&[
+                    Arg::with_name("base")
+                        .required(true)
+                        .help("The common ancestor version (%O)"),
+                    Arg::with_name("ours")
+                        .required(true)
+                        .help("The current version (%A), where the result is written"),
+                    Arg::with_name("theirs")
+                        .required(true)
+                        .help("The other branch version (%B)"),
+                ]),
+        )
+        .subcommand(
+            App::new("config")
+                .about("Manage Configuration Items")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("get")
+                        .about("Show the value of a Configuration Item")
+                        .arg(
+                            Arg::with_name("name")
+                                .short("n")
+                                .long("name")
+                                .required(true)
+                                .takes_value(true)
+                                .help("the name of the property"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("set")
+                        .about("Update Configuration Item with specified value")
+                        .arg(
+                            Arg::with_name("name")
+                                .short("n")
+                                .long("name")
+                                .required(true)
+                                .takes_value(true)
+                                .help("the name of the property"),
+                        )
+                        .arg(
+                            Arg::with_name("value")
+                                .long("value")
+                                .required(true)
+                                .takes_value(true)
+                                .help("the value of the property"),
+                        )
+                        .arg(
+                            Arg::with_name("create")
+                                .long("create")
+                                .help("Create the directory if the property is a path which does not exist"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("unset")
+                        .about("Set a Configuration Item back to its default value")
+                        .arg(
+                            Arg::with_name("name")
+                                .short("n")
+                                .long("name")
+                                .required(true)
+                                .takes_value(true)
+                                .help("the name of the property"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("reset")
+                        .about("Set all the Configuration Items back to their default value"),
+                )
+                .subcommand(
+                    SubCommand::with_name("path").about("Show the path of the configuration file"),
+                )
+                .subcommand(
+                    SubCommand::with_name("edit")
+                        .about("Edit the configuration file in $VISUAL or $EDITOR and check it"),
+                )
+                .subcommand(
+                    SubCommand::with_name("list").about("List All the Configuration Items"),
+                ),
         )
         .subcommand(
-            App::new("config")
-                .about("Manage Configuration Items")
+            App::new("workspace")
+                .about("Manage the Workspaces, i.e. named Decision Records repositories")
                 .setting(AppSettings::SubcommandRequiredElseHelp)
                 .subcommand(
-                    SubCommand::with_name("set")
-                        .about("Update Configuration Item with specified value")
-                        .arg(
+                    SubCommand::with_name("add")
+                        .about("Add a Workspace")
+                        .args(&[
                             Arg::with_name("name")
-                                .short("n")
-                                .long("name")
                                 .required(true)
+                                .help("the name of the workspace, e.g. platform"),
+                            Arg::with_name("dir")
+                                .required(true)
+                                .help("the directory of the Decision Records of the workspace"),
+                            Arg::with_name("template-dir")
+                                .long("template-dir")
                                 .takes_value(true)
-                                .help("the name of the property"),
-                        )
+                                .help("the directory of the templates (default: <dir>/templates)"),
+                            Arg::with_name("search-index")
+                                .long("search-index")
+                                .takes_value(true)
+                                .help("the directory of the search index (default: <dir>/.index)"),
+                            Arg::with_name("create")
+                                .long("create")
+                                .help("Create the directory of the Decision Records if it does not exist"),
+                        ]),
+                )
+                .subcommand(
+                    SubCommand::with_name("use")
+                        .about("Make a Workspace the current one")
                         .arg(
-                            Arg::with_name("value")
-                                .short("v")
-                                .long("value")
+                            Arg::with_name("name")
                                 .required(true)
-                                .takes_value(true)
-                                .help("the value of the property"),
+                                .help("the name of the workspace"),
                         ),
                 )
-                .subcommand(
-                    SubCommand::with_name("list").about("List All the Configuration Items"),
-                ),
+                .subcommand(SubCommand::with_name("list").about("List All the Workspaces")),
         )
         .subcommand(
             App::new("tags")
                 .about("Manage Tags")
                 .setting(AppSettings::SubcommandRequiredElseHelp)
-                .subcommand(SubCommand::with_name("list").about("List All the Tags")),
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("List All the Tags")
+                        .arg(
+                            Arg::with_name("tree").long("tree").help(
+                                "Show tags as a tree (e.g. security/authn) with rollup counts",
+                            ),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("add")
+                        .about("Add a tag to a Decision Record")
+                        .args(&[
+                            Arg::with_name("path")
+                                .short("p")
+                                .long("path")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Give the path of your Decision Record"),
+                            Arg::with_name("tag")
+                                .short("t")
+                                .long("tag")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The tag to add"),
+                            Arg::with_name("dry-run")
+                                .long("dry-run")
+                                .help("Show the file that would change without modifying it"),
+                        ]),
+                )
+                .subcommand(
+                    SubCommand::with_name("remove")
+                        .about("Remove a tag from a Decision Record")
+                        .args(&[
+                            Arg::with_name("path")
+                                .short("p")
+                                .long("path")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Give the path of your Decision Record"),
+                            Arg::with_name("tag")
+                                .short("t")
+                                .long("tag")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The tag to remove"),
+                            Arg::with_name("dry-run")
+                                .long("dry-run")
+                                .help("Show the file that would change without modifying it"),
+                        ]),
+                )
+                .subcommand(
+                    SubCommand::with_name("rename")
+                        .about("Rename a tag across all the Decision Records")
+                        .args(&[
+                            Arg::with_name("from")
+                                .short("f")
+                                .long("from")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The tag to rename"),
+                            Arg::with_name("to")
+                                .short("t")
+                                .long("to")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The new name of the tag"),
+                            Arg::with_name("dry-run")
+                                .long("dry-run")
+                                .help("Show every file that would change without modifying them"),
+                        ]),
+                )
+                .subcommand(
+                    SubCommand::with_name("merge")
+                        .about("Merge several tags into one across all the Decision Records")
+                        .args(&[
+                            Arg::with_name("from")
+                                .short("f")
+                                .long("from")
+                                .takes_value(true)
+                                .multiple(true)
+                                .number_of_values(1)
+                                .required(true)
+                                .help("The tags to merge (e.g. --from Security --from sec)"),
+                            Arg::with_name("into")
+                                .short("i")
+                                .long("into")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The tag to merge into"),
+                            Arg::with_name("dry-run")
+                                .long("dry-run")
+                                .help("Show every file that would change without modifying them"),
+                        ]),
+                ),
         )
         .subcommand(
             App::new("lf")
                 .about("Manages ADRs lifecycle")
                 .setting(AppSettings::SubcommandRequiredElseHelp)
+                .arg(
+                    Arg::with_name("no-commit")
+                        .long("no-commit")
+                        .global(true)
+                        .help("Do not commit the changes, even if auto_commit is enabled"),
+                )
                 .subcommand(
                     SubCommand::with_name("new")
                         .about("Creates a new Decision Record")
@@ -258,6 +1259,17 @@ fn main() {
                                 .takes_value(true)
                                 .required(false)
                                 .help("Specify relative path (nested directories)"),
+                        )
+                        .arg(
+                            Arg::with_name("tag")
+                                .long("tag")
+                                .takes_value(true)
+                                .multiple(true)
+                                .number_of_values(1)
+                                .required(false)
+                                .help(
+                                    "Tag the Decision Record (replaces the tags of the template)",
+                                ),
                         ),
                 )
                 .subcommand(
@@ -329,18 +1341,203 @@ fn main() {
                         ),
                 ),
         )
+        .subcommand(
+            App::new("review")
+                .about("Manages the reviews of the Decision Records")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .arg(
+                    Arg::with_name("no-commit")
+                        .long("no-commit")
+                        .global(true)
+                        .help("Do not commit the changes, even if auto_commit is enabled"),
+                )
+                .subcommand(
+                    SubCommand::with_name("request")
+                        .about("Update the Status to Review and name the reviewers")
+                        .version("0.1.0")
+                        .arg(
+                            Arg::with_name("path")
+                                .short("p")
+                                .long("path")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Give the path of your Decision Record"),
+                        )
+                        .arg(
+                            Arg::with_name("reviewer")
+                                .short("r")
+                                .long("reviewer")
+                                .takes_value(true)
+                                .multiple(true)
+                                .number_of_values(1)
+                                .help("The reviewer, e.g. @alice"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("approve")
+                        .about("Approve the Decision Record")
+                        .version("0.1.0")
+                        .arg(
+                            Arg::with_name("path")
+                                .short("p")
+                                .long("path")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Give the path of your Decision Record"),
+                        )
+                        .arg(
+                            Arg::with_name("as")
+                                .long("as")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The reviewer, e.g. @alice"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("reject")
+                        .about("Reject the Decision Record and send it back to wip")
+                        .version("0.1.0")
+                        .arg(
+                            Arg::with_name("path")
+                                .short("p")
+                                .long("path")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Give the path of your Decision Record"),
+                        )
+                        .arg(
+                            Arg::with_name("as")
+                                .long("as")
+                                .takes_value(true)
+                                .required(true)
+                                .help("The reviewer, e.g. @alice"),
+                        )
+                        .arg(
+                            Arg::with_name("note")
+                                .short("n")
+                                .long("note")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Why the Decision Record is rejected"),
+                        ),
+                )
+                .subcommand(
+                    SubCommand::with_name("status")
+                        .about("List the reviews of the Decision Record")
+                        .version("0.1.0")
+                        .arg(
+                            Arg::with_name("path")
+                                .short("p")
+                                .long("path")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Give the path of your Decision Record"),
+                        ),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("stats")
+                .about("Statistics and flow metrics of the Decision Records")
+                .version("0.1.0")
+                .args(&filter_args())
+                .arg(
+                    Arg::with_name("format")
+                        .short("f")
+                        .long("format")
+                        .takes_value(true)
+                        .possible_values(&["table", "json"])
+                        .default_value("table")
+                        .help("The output format"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("show")
+                .about("Show the details of a Decision Record (with the dates of its git history)")
+                .version("0.1.0")
+                .args(&[
+                    Arg::with_name("path")
+                        .short("p")
+                        .long("path")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Give the path of your Decision Record"),
+                    Arg::with_name("rev")
+                        .long("rev")
+                        .takes_value(true)
+                        .help("Read the Decision Records as they were at this git revision (e.g. a tag)"),
+                ]),
+        )
+        .subcommand(
+            SubCommand::with_name("changelog")
+                .about("The changes of the Decision Records between two git revisions, for release notes")
+                .version("0.1.0")
+                .args(&[
+                    Arg::with_name("range")
+                        .required(true)
+                        .help("The git revisions, e.g. v3.1..v3.2 (v3.1.. means v3.1..HEAD)"),
+                    Arg::with_name("format")
+                        .short("f")
+                        .long("format")
+                        .takes_value(true)
+                        .possible_values(&["asciidoc", "markdown"])
+                        .default_value("asciidoc")
+                        .help("The output format"),
+                ]),
+        )
+        .subcommand(
+            SubCommand::with_name("due")
+                .about("List the Decision Records which are wip for too long or have to be revisited")
+                .version("0.1.0")
+                .arg(
+                    Arg::with_name("wip-max-age")
+                        .long("wip-max-age")
+                        .takes_value(true)
+                        .help("Number of days a Decision Record may stay wip (overrides the wip_max_age_days configuration)"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("lint")
+                .about("Check all the Decision Records (e.g. tags against the allowed tags)")
+                .version("0.1.0")
+                .arg(
+                    Arg::with_name("staged")
+                        .long("staged")
+                        .help("Only check the Decision Records staged in git, including their status changes since HEAD"),
+                ),
+        )
+        .subcommand(
+            App::new("hook")
+                .about("Manage the git hooks of the Decision Records")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("install")
+                        .about("Write the git pre-commit hook which runs lint --staged")
+                        .arg(
+                            Arg::with_name("force")
+                                .long("force")
+                                .help("Replace an existing pre-commit hook"),
+                        ),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("search")
                 .about("Search across all ADRs")
                 .version("0.1.0")
                 .args(&[
                     Arg::with_name("query")
-                        .short("q")
                         .long("query")
                         .takes_value(true)
                         .required(true)
                         .conflicts_with("build-index")
                         .help("Provide your search query"),
+                    Arg::with_name("tag")
+                        .short("t")
+                        .long("tag")
+                        .takes_value(true)
+                        .requires("query")
+                        .help(
+                            "Only keep the results tagged with this tag (or one of its children)",
+                        ),
                     Arg::with_name("build-index")
                         .short("b")
                         .long("build-index")
@@ -348,27 +1545,127 @@ fn main() {
                         .required(true)
                         .conflicts_with("query")
                         .help("Build the index based on available ADRs."),
+                    Arg::with_name("all-workspaces")
+                        .long("all-workspaces")
+                        .help("Use the index spanning all the workspaces (cf. federated_search_index)"),
                 ]),
         )
         .get_matches();
 
+    //the command line takes precedence over the configuration files and the environment variables
+    let mut cli_properties = Vec::new();
+    if let Some(src_dir) = global_value_of(&_options, "src-dir") {
+        cli_properties.push((String::from("adr_src_dir"), String::from(src_dir)));
+    }
+    if let Some(log_level) = global_value_of(&_options, "log-level") {
+        cli_properties.push((String::from("log_level"), String::from(log_level)));
+    }
+    if let Some(workspace) = global_value_of(&_options, "workspace") {
+        cli_properties.push((String::from("workspace"), String::from(workspace)));
+    }
+    adr_config::config::set_cli_overrides(
+        global_value_of(&_options, "config").map(PathBuf::from),
+        cli_properties,
+    );
+    //a configuration which cannot be read stops everything but the commands to fix it
+    if _options.subcommand_name() != Some("config")
+        && _options.subcommand_name() != Some("workspace")
+    {
+        let errors = adr_config::config::get_config_layers().errors;
+        if !errors.is_empty() {
+            for error in errors.iter() {
+                eprintln!("{}", error);
+            }
+            std::process::exit(1);
+        }
+    }
+
+    //the logger of all the crates, set once for all
+    let cfg: AdrToolConfig = adr_config::config::get_config();
+    let log_settings = LogSettings {
+        //the global flags are propagated to all the levels of the matches, whether they are given before or after the
+        //subcommands (e.g. `adr -vv list` or `adr list -vv`), so they must not be summed across the levels
+        level: get_verbosity(
+            cfg.log_level,
+            _options.occurrences_of("verbose"),
+            _options.occurrences_of("quiet"),
+        ),
+        format: match global_value_of(&_options, "log-format") {
+            Some(format) => format.parse::<LogFormat>().unwrap_or(LogFormat::TEXT),
+            None => LogFormat::TEXT,
+        },
+        file: global_value_of(&_options, "log-file").map(PathBuf::from),
+    };
+    if let Err(why) = adr_config::logger::init(&log_settings) {
+        eprintln!("Unable to log to the file - {}", why);
+        std::process::exit(1);
+    }
+
     //
     match _options.subcommand() {
-        ("list", Some(_matches)) => {
-            list_all_adr().unwrap();
-        }
-        ("init", Some(_matches)) => {
+        ("list", Some(matches)) => match build_filter(matches) {
+            Ok(filter) => {
+                if let Err(why) = list_all_adr(
+                    &filter,
+                    matches.value_of("rev"),
+                    matches.is_present("all-workspaces"),
+                ) {
+                    eprintln!("{}", why);
+                    std::process::exit(1);
+                }
+            }
+            Err(why) => {
+                eprintln!("{}", why);
+                std::process::exit(1);
+            }
+        },
+        ("init", Some(matches)) => {
             init().unwrap();
+            if matches.is_present("git") {
+                if let Err(why) = init_git() {
+                    eprintln!("{}", why);
+                    std::process::exit(1);
+                }
+            }
+        }
+        ("merge-driver", Some(matches)) => {
+            match adr_core::adr_merge::merge_adr_files(
+                Path::new(matches.value_of("base").unwrap()),
+                Path::new(matches.value_of("ours").unwrap()),
+                Path::new(matches.value_of("theirs").unwrap()),
+            ) {
+                Ok(true) => (),
+                Ok(false) => std::process::exit(1),
+                Err(why) => {
+                    eprintln!("{}", why);
+                    std::process::exit(2);
+                }
+            }
         }
         ("lf", Some(matches)) => match matches.subcommand() {
             ("new", Some(matches)) => {
                 if matches.is_present("title") {
-                    adr_core::adr_repo::create_adr(
+                    let tags: Vec<&str> = match matches.values_of("tag") {
+                        Some(values) => values.collect(),
+                        None => Vec::new(),
+                    };
+                    match adr_core::adr_repo::create_adr_with_tags(
                         adr_config::config::get_config(),
                         matches.value_of("path"),
                         matches.value_of("title").unwrap(),
-                    )
-                    .unwrap();
+                        &tags,
+                    ) {
+                        Ok(Some(path)) => commit_adrs(
+                            matches,
+                            &[path.as_path()],
+                            &adr_core::adr_git::commit_message(&path, "new"),
+                        ),
+                        Ok(None) => std::process::exit(1),
+                        Err(why) => {
+                            eprintln!("{}", why);
+                            std::process::exit(1);
+                        }
+                    }
                 }
             }
             ("decided", Some(set_matches)) => {
@@ -377,29 +1674,40 @@ fn main() {
                     let cfg: AdrToolConfig = adr_config::config::get_config();
                     let base_path = Path::new(&cfg.adr_src_dir);
 
-                    adr_core::adr_repo::transition_to_decided(base_path, file_path).unwrap();
+                    if !adr_core::adr_repo::transition_to_decided_with_quorum(
+                        base_path,
+                        file_path,
+                        cfg.review_quorum,
+                    )
+                    .unwrap()
+                    {
+                        std::process::exit(1);
+                    }
+                    commit_adrs(
+                        set_matches,
+                        &[Path::new(file_path)],
+                        &adr_core::adr_git::commit_message(Path::new(file_path), "decided"),
+                    );
                 }
             }
             ("completed-by", Some(set_matches)) => {
                 if set_matches.is_present("path") && set_matches.is_present("by") {
-                    let cfg: AdrToolConfig = adr_config::config::get_config();
-                    let base_path = Path::new(&cfg.adr_src_dir);
-                    let file_path = set_matches.value_of("path").unwrap();
-                    let by_path = set_matches.value_of("by").unwrap();
-
-                    adr_core::adr_repo::transition_to_completed_by(base_path, file_path, by_path)
-                        .unwrap();
+                    if let Err(why) =
+                        transition_by(set_matches, TransitionStatus::COMPLETED, "completes")
+                    {
+                        eprintln!("{}", why);
+                        std::process::exit(1);
+                    }
                 }
             }
             ("superseded-by", Some(set_matches)) => {
                 if set_matches.is_present("path") && set_matches.is_present("by") {
-                    let cfg: AdrToolConfig = adr_config::config::get_config();
-                    let base_path = Path::new(&cfg.adr_src_dir);
-                    let file_path = set_matches.value_of("path").unwrap();
-                    let by_path = set_matches.value_of("by").unwrap();
-
-                    adr_core::adr_repo::transition_to_superseded_by(base_path, file_path, by_path)
-                        .unwrap();
+                    if let Err(why) =
+                        transition_by(set_matches, TransitionStatus::SUPERSEDED, "supersedes")
+                    {
+                        eprintln!("{}", why);
+                        std::process::exit(1);
+                    }
                 }
             }
             ("obsoleted", Some(set_matches)) => {
@@ -408,38 +1716,262 @@ fn main() {
                     let base_path = Path::new(&cfg.adr_src_dir);
                     let file_path = set_matches.value_of("path").unwrap();
 
-                    adr_core::adr_repo::transition_to_obsoleted(base_path, file_path).unwrap();
+                    if adr_core::adr_repo::transition_to_obsoleted(base_path, file_path).unwrap() {
+                        commit_adrs(
+                            set_matches,
+                            &[Path::new(file_path)],
+                            &adr_core::adr_git::commit_message(Path::new(file_path), "obsoleted"),
+                        );
+                    }
                 }
             }
 
             _ => unreachable!(),
         },
+        ("review", Some(review_matches)) => {
+            let cfg: AdrToolConfig = adr_config::config::get_config();
+            let base_path = Path::new(&cfg.adr_src_dir);
+            let (name, matches) = match review_matches.subcommand() {
+                (name, Some(matches)) => (name, matches),
+                _ => unreachable!(),
+            };
+            let file_path = matches.value_of("path").unwrap();
+            let (reviewed, action) = match name {
+                "request" => {
+                    let reviewers: Vec<&str> = match matches.values_of("reviewer") {
+                        Some(values) => values.collect(),
+                        None => Vec::new(),
+                    };
+                    (
+                        adr_core::adr_repo::request_review(base_path, file_path, &reviewers),
+                        Some(String::from("review")),
+                    )
+                }
+                "approve" => (
+                    adr_core::adr_repo::approve_review(
+                        base_path,
+                        file_path,
+                        matches.value_of("as").unwrap(),
+                    ),
+                    Some(format!("approved by {}", matches.value_of("as").unwrap())),
+                ),
+                "reject" => (
+                    adr_core::adr_repo::reject_review(
+                        base_path,
+                        file_path,
+                        matches.value_of("as").unwrap(),
+                        matches.value_of("note").unwrap(),
+                    ),
+                    Some(format!("rejected by {}", matches.value_of("as").unwrap())),
+                ),
+                "status" => (list_reviews(file_path).map(|_| true), None),
+                _ => unreachable!(),
+            };
+            if !reviewed.unwrap() {
+                std::process::exit(1);
+            }
+            if let Some(action) = action {
+                commit_adrs(
+                    matches,
+                    &[Path::new(file_path)],
+                    &adr_core::adr_git::commit_message(Path::new(file_path), &action),
+                );
+            }
+        }
         ("config", Some(config_matches)) => match config_matches.subcommand() {
             ("list", Some(_remote_matches)) => {
                 list_all_config().unwrap();
             }
+            ("get", Some(get_matches)) => {
+                if let Err(why) = get_config(get_matches.value_of("name").unwrap()) {
+                    eprintln!("{}", why);
+                    std::process::exit(1);
+                }
+            }
             ("set", Some(set_matches)) => {
-                set_config(
+                if let Err(why) = set_config(
                     set_matches.value_of("name").unwrap(),
                     set_matches.value_of("value").unwrap(),
+                    set_matches.is_present("create"),
+                ) {
+                    eprintln!("{}", why);
+                    std::process::exit(1);
+                }
+            }
+            ("unset", Some(unset_matches)) => {
+                if let Err(why) =
+                    adr_config::config::unset_config(unset_matches.value_of("name").unwrap())
+                {
+                    eprintln!("{}", why);
+                    std::process::exit(1);
+                }
+            }
+            ("reset", Some(_reset_matches)) => {
+                if let Err(why) = adr_config::config::reset_config() {
+                    eprintln!("{}", why);
+                    std::process::exit(1);
+                }
+            }
+            ("path", Some(_path_matches)) => {
+                match adr_config::config::get_config_path("adrust-tools") {
+                    Some(path) => println!("{}", path.display()),
+                    None => std::process::exit(1),
+                }
+            }
+            ("edit", Some(_edit_matches)) => {
+                if let Err(why) = edit_config() {
+                    eprintln!("{}", why);
+                    std::process::exit(1);
+                }
+            }
+            _ => unreachable!(),
+        },
+        ("workspace", Some(workspace_matches)) => match workspace_matches.subcommand() {
+            ("add", Some(add_matches)) => {
+                if let Err(why) = add_workspace(add_matches) {
+                    eprintln!("{}", why);
+                    std::process::exit(1);
+                }
+            }
+            ("use", Some(use_matches)) => {
+                if let Err(why) =
+                    adr_config::config::use_workspace(use_matches.value_of("name").unwrap())
+                {
+                    eprintln!("{}", why);
+                    std::process::exit(1);
+                }
+            }
+            ("list", Some(_list_matches)) => {
+                list_all_workspaces().unwrap();
+            }
+            _ => unreachable!(),
+        },
+        ("tags", Some(tags_matches)) => match tags_matches.subcommand() {
+            ("list", Some(list_matches)) => {
+                if list_matches.is_present("tree") {
+                    list_tags_tree().unwrap();
+                } else {
+                    list_all_tags().unwrap();
+                }
+            }
+            ("add", Some(add_matches)) => {
+                if let Err(why) = add_tag(
+                    add_matches.value_of("path").unwrap(),
+                    add_matches.value_of("tag").unwrap(),
+                    add_matches.is_present("dry-run"),
+                ) {
+                    eprintln!("{}", why);
+                    std::process::exit(1);
+                }
+            }
+            ("remove", Some(remove_matches)) => {
+                remove_tag(
+                    remove_matches.value_of("path").unwrap(),
+                    remove_matches.value_of("tag").unwrap(),
+                    remove_matches.is_present("dry-run"),
                 )
                 .unwrap();
             }
+            ("rename", Some(rename_matches)) => {
+                if let Err(why) = merge_tags(
+                    vec![rename_matches.value_of("from").unwrap()],
+                    rename_matches.value_of("to").unwrap(),
+                    rename_matches.is_present("dry-run"),
+                ) {
+                    eprintln!("{}", why);
+                    std::process::exit(1);
+                }
+            }
+            ("merge", Some(merge_matches)) => {
+                if let Err(why) = merge_tags(
+                    merge_matches.values_of("from").unwrap().collect(),
+                    merge_matches.value_of("into").unwrap(),
+                    merge_matches.is_present("dry-run"),
+                ) {
+                    eprintln!("{}", why);
+                    std::process::exit(1);
+                }
+            }
             _ => unreachable!(),
         },
-        ("tags", Some(tags_matches)) => match tags_matches.subcommand() {
-            ("list", Some(_remote_matches)) => {
-                list_all_tags().unwrap();
+        ("stats", Some(matches)) => match build_filter(matches) {
+            Ok(filter) => stats(&filter, matches.value_of("format").unwrap()).unwrap(),
+            Err(why) => {
+                eprintln!("{}", why);
+                std::process::exit(1);
+            }
+        },
+        ("show", Some(show_matches)) => {
+            if let Err(why) = show(
+                show_matches.value_of("path").unwrap(),
+                show_matches.value_of("rev"),
+            ) {
+                eprintln!("{}", why);
+                std::process::exit(1);
+            }
+        }
+        ("changelog", Some(changelog_matches)) => {
+            if let Err(why) = changelog(
+                changelog_matches.value_of("range").unwrap(),
+                changelog_matches.value_of("format").unwrap(),
+            ) {
+                eprintln!("{}", why);
+                std::process::exit(1);
+            }
+        }
+        ("due", Some(due_matches)) => {
+            let wip_max_age_days = match due_matches.value_of("wip-max-age") {
+                Some(val) => match val.parse() {
+                    Ok(days) => Some(days),
+                    Err(_why) => {
+                        eprintln!("Invalid number of days [{}]", val);
+                        std::process::exit(1);
+                    }
+                },
+                None => None,
+            };
+            if !due(wip_max_age_days).unwrap() {
+                std::process::exit(1);
+            }
+        }
+        ("lint", Some(matches)) => match lint(matches.is_present("staged")) {
+            Ok(true) => (),
+            Ok(false) => std::process::exit(1),
+            Err(why) => {
+                eprintln!("{}", why);
+                std::process::exit(1);
+            }
+        },
+        ("hook", Some(hook_matches)) => match hook_matches.subcommand() {
+            ("install", Some(install_matches)) => {
+                match install_hook(install_matches.is_present("force")) {
+                    Ok(true) => println!("pre-commit hook installed"),
+                    Ok(false) => println!("pre-commit hook already installed"),
+                    Err(why) => {
+                        eprintln!("{}", why);
+                        std::process::exit(1);
+                    }
+                }
             }
             _ => unreachable!(),
         },
         ("search", Some(search_matches)) => {
             if search_matches.is_present("query") {
                 let query = search_matches.value_of("query").unwrap().to_string();
-                search(query).unwrap();
+                if let Err(why) = search(
+                    query,
+                    search_matches.value_of("tag"),
+                    search_matches.is_present("all-workspaces"),
+                ) {
+                    eprintln!("{}", why);
+                    std::process::exit(1);
+                }
             }
             if search_matches.is_present("build-index") {
-                build_index().unwrap();
+                if let Err(why) = build_index(search_matches.is_present("all-workspaces")) {
+                    eprintln!("{}", why);
+                    std::process::exit(1);
+                }
             }
         }
 